@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{DynResult, Puzzle};
+
+// Recorded answers, a `day part answer` line each. `#` starts a comment.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u8), String>,
+}
+
+impl Answers {
+    // `answers.txt` next to the inputs.
+    pub fn default_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("answers.txt")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> DynResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read answers {}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> DynResult<Self> {
+        let mut answers = Self::default();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = || format!("Invalid answer line {}: {}", idx + 1, line);
+            let mut words = line.splitn(3, char::is_whitespace);
+            let day = words.next().and_then(|day| day.parse().ok());
+            let part = words.next().and_then(|part| part.parse().ok());
+            match (day, part, words.next()) {
+                (Some(day), Some(part), Some(answer)) => answers.insert(day, part, answer.trim()),
+                _ => return Err(error().into()),
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn insert(&mut self, day: u32, part: u8, answer: &str) {
+        self.answers.insert((day, part), answer.to_string());
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Correct,
+    Mismatch,
+    Unrecorded,
+    Failed(String),
+}
+
+pub struct CheckRow {
+    pub day: u32,
+    pub part: u8,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub time: Duration,
+    pub status: Status,
+}
+
+pub struct Check {
+    pub rows: Vec<CheckRow>,
+}
+
+impl Check {
+    // Runs every solved part of the puzzles. A puzzle whose input cannot be
+    // read or parsed fails all its parts.
+    pub fn run(puzzles: &[Puzzle], answers: &Answers) -> Self {
+        let mut rows = Vec::new();
+        for puzzle in puzzles {
+            let report = puzzle.input(None).and_then(|input| puzzle.run(&input, &[]));
+            let results: Vec<_> = match report {
+                Ok(report) => report
+                    .parts
+                    .into_iter()
+                    .map(|part| (part.part, part.answer, part.time))
+                    .collect(),
+                Err(error) => (1..=puzzle.parts)
+                    .map(|part| (part, Err(error.to_string()), Duration::default()))
+                    .collect(),
+            };
+            for (part, answer, time) in results {
+                let expected = answers.get(puzzle.day, part).map(str::to_string);
+                let status = match (&answer, &expected) {
+                    (Err(error), _) => Status::Failed(error.clone()),
+                    (Ok(_), None) => Status::Unrecorded,
+                    (Ok(actual), Some(expected)) if actual == expected => Status::Correct,
+                    (Ok(_), Some(_)) => Status::Mismatch,
+                };
+                rows.push(CheckRow {
+                    day: puzzle.day,
+                    part,
+                    expected,
+                    actual: answer.ok(),
+                    time,
+                    status,
+                });
+            }
+        }
+        Self { rows }
+    }
+
+    // A part without a recorded answer fails too, so a line lost from the
+    // answers file does not go unnoticed.
+    pub fn passed(&self) -> bool {
+        self.rows.iter().all(|row| row.status == Status::Correct)
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.rows.iter().map(|row| row.time).sum()
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cell = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "-".to_string());
+        let width = |column: &dyn Fn(&CheckRow) -> String| {
+            self.rows
+                .iter()
+                .map(|row| column(row).len())
+                .fold(8, usize::max)
+        };
+        let expected_width = width(&|row| cell(&row.expected));
+        let actual_width = width(&|row| cell(&row.actual));
+        writeln!(
+            f,
+            "Day Part  {:<ew$}  {:<aw$}  {:>10}  Status",
+            "Expected",
+            "Actual",
+            "Time",
+            ew = expected_width,
+            aw = actual_width
+        )?;
+        for row in &self.rows {
+            let status = match &row.status {
+                Status::Correct => "ok".to_string(),
+                Status::Mismatch => "MISMATCH".to_string(),
+                Status::Unrecorded => "NO ANSWER RECORDED".to_string(),
+                Status::Failed(error) => format!("FAILED: {}", error),
+            };
+            writeln!(
+                f,
+                "{:>3} {:>4}  {:<ew$}  {:<aw$}  {:>10}  {}",
+                row.day,
+                row.part,
+                cell(&row.expected),
+                cell(&row.actual),
+                format!("{:.2?}", row.time),
+                status,
+                ew = expected_width,
+                aw = actual_width
+            )?;
+        }
+        let failed = self.rows.iter().filter(|row| row.status != Status::Correct);
+        writeln!(
+            f,
+            "{} of {} parts failed, total time {:.2?}",
+            failed.count(),
+            self.rows.len(),
+            self.total_time()
+        )
+    }
+}
+
+#[test]
+fn test_check() {
+    let answers =
+        Answers::parse("# day part answer\n0 1 6\n\n0 2 ABC DEF # spaces kept\n").unwrap();
+    assert_eq!(answers.get(0, 2), Some("ABC DEF"));
+    assert!(Answers::parse("0 x 6").is_err());
+
+    let puzzles = [Puzzle::of::<crate::Sum>()];
+    let check = Check::run(&puzzles, &answers);
+    assert_eq!(check.rows.len(), 1);
+    assert_eq!(check.rows[0].status, Status::Correct);
+    assert!(check.passed());
+
+    let check = Check::run(&puzzles, &Answers::parse("0 1 7").unwrap());
+    assert_eq!(check.rows[0].status, Status::Mismatch);
+    assert!(!check.passed());
+    assert!(check.to_string().contains("MISMATCH"));
+
+    let check = Check::run(&puzzles, &Answers::default());
+    assert_eq!(check.rows[0].status, Status::Unrecorded);
+    assert!(!check.passed());
+}
@@ -0,0 +1,214 @@
+mod answers;
+
+pub use answers::*;
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub type DynResult<T> = Result<T, Box<dyn Error>>;
+
+pub trait Solution {
+    const DAY: u32;
+    // Only the first PARTS parts are solved.
+    const PARTS: u8 = 2;
+    // For puzzles whose input is given inline instead of as a file.
+    const INPUT: Option<&'static str> = None;
+
+    type Input;
+
+    fn parse(input: &str) -> DynResult<Self::Input>;
+    fn part1(input: &Self::Input) -> DynResult<String>;
+
+    fn part2(_input: &Self::Input) -> DynResult<String> {
+        Err("Part 2 is not solved".into())
+    }
+}
+
+// Inputs live next to the workspace manifest as `dayN-input.txt`, wherever
+// the binary is started from.
+pub fn input_path(day: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{}-input.txt", day))
+}
+
+pub fn read_input(day: u32) -> DynResult<Vec<u8>> {
+    let path = input_path(day);
+    std::fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e).into())
+}
+
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Result<String, String>,
+    pub time: Duration,
+}
+
+pub struct Report {
+    pub day: u32,
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl Report {
+    pub fn failed(&self) -> bool {
+        self.parts.iter().any(|part| part.answer.is_err())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Day {} (parsed in {:.2?})", self.day, self.parse_time)?;
+        for part in &self.parts {
+            match &part.answer {
+                Ok(answer) if answer.contains('\n') => {
+                    writeln!(f, "  Part {} ({:.2?}):", part.part, part.time)?;
+                    for line in answer.lines() {
+                        writeln!(f, "    {}", line)?;
+                    }
+                }
+                Ok(answer) => writeln!(f, "  Part {}: {} ({:.2?})", part.part, answer, part.time)?,
+                Err(error) => writeln!(f, "  Part {}: error: {}", part.part, error)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Type erased solution, for running days picked at runtime.
+#[derive(Clone, Copy)]
+pub struct Puzzle {
+    pub day: u32,
+    pub parts: u8,
+    inline_input: Option<&'static str>,
+    run: fn(&str, &[u8]) -> DynResult<Report>,
+}
+
+impl Puzzle {
+    pub fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            parts: S::PARTS,
+            inline_input: S::INPUT,
+            run: run::<S>,
+        }
+    }
+
+    // The given file, or else the default input of the day.
+    pub fn input(&self, path: Option<&Path>) -> DynResult<String> {
+        let bytes = match (path, self.inline_input) {
+            (Some(path), _) => {
+                std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+            }
+            (None, Some(input)) => return Ok(input.to_string()),
+            (None, None) => read_input(self.day)?,
+        };
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    // Runs the given parts, all solved ones when empty.
+    pub fn run(&self, input: &str, parts: &[u8]) -> DynResult<Report> {
+        if let Some(part) = parts.iter().find(|part| !(1..=self.parts).contains(*part)) {
+            return Err(format!("Day {} has no part {}", self.day, part).into());
+        }
+        let all: Vec<u8> = (1..=self.parts).collect();
+        (self.run)(input, if parts.is_empty() { &all } else { parts })
+    }
+}
+
+fn run<S: Solution>(input: &str, parts: &[u8]) -> DynResult<Report> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_time = start.elapsed();
+    let parts = parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => S::part1(&parsed),
+                _ => S::part2(&parsed),
+            };
+            PartResult {
+                part: *part,
+                answer: answer.map_err(|e| e.to_string()),
+                time: start.elapsed(),
+            }
+        })
+        .collect();
+    Ok(Report {
+        day: S::DAY,
+        parse_time,
+        parts,
+    })
+}
+
+// `--part N` and `--input PATH`, other arguments are left to the caller.
+#[derive(Default)]
+pub struct Args {
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> DynResult<Self> {
+        let value = |name: &str| args.iter().skip_while(|arg| *arg != name).nth(1);
+        Ok(Self {
+            part: match value("--part") {
+                Some(part) => Some(part.parse()?),
+                None => None,
+            },
+            input: value("--input").map(PathBuf::from),
+        })
+    }
+
+    pub fn parts(&self) -> Vec<u8> {
+        self.part.into_iter().collect()
+    }
+}
+
+// Entry point of the single day binaries.
+pub fn main<S: Solution>() -> DynResult<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let args = Args::parse(&args)?;
+    let puzzle = Puzzle::of::<S>();
+    let report = puzzle.run(&puzzle.input(args.input.as_deref())?, &args.parts())?;
+    print!("{}", report);
+    if report.failed() {
+        return Err(format!("Day {} failed", puzzle.day).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+struct Sum;
+
+#[cfg(test)]
+impl Solution for Sum {
+    const DAY: u32 = 0;
+    const PARTS: u8 = 1;
+    const INPUT: Option<&'static str> = Some("1 2 3");
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> DynResult<Vec<u32>> {
+        Ok(input
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Vec<u32>) -> DynResult<String> {
+        Ok(input.iter().sum::<u32>().to_string())
+    }
+}
+
+#[test]
+fn test_puzzle() {
+    let puzzle = Puzzle::of::<Sum>();
+    let input = puzzle.input(None).unwrap();
+    let report = puzzle.run(&input, &[]).unwrap();
+    assert_eq!(report.parts.len(), 1);
+    assert_eq!(report.parts[0].answer, Ok("6".to_string()));
+    assert!(puzzle.run(&input, &[2]).is_err());
+    assert!(puzzle.run("x", &[1]).is_err());
+}
@@ -0,0 +1,34 @@
+// Every solved part on its real input, the input parsed outside the timing.
+// scripts/bench-compare.sh runs it against the baseline of a git revision.
+use aoc_runner::{Puzzle, Solution};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    let input = Puzzle::of::<S>().input(None).unwrap();
+    let input = S::parse(&input).unwrap();
+    let mut group = c.benchmark_group(format!("day{}", S::DAY));
+    group.sample_size(10);
+    group.bench_function("part1", |b| b.iter(|| S::part1(&input).unwrap()));
+    if S::PARTS > 1 {
+        group.bench_function("part2", |b| b.iter(|| S::part2(&input).unwrap()));
+    }
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_day::<day1::Day1>(c);
+    bench_day::<day2::Day2>(c);
+    bench_day::<day3::Day3>(c);
+    bench_day::<day4::Day4>(c);
+    bench_day::<day5::Day5>(c);
+    bench_day::<day6::Day6>(c);
+    bench_day::<day7::Day7>(c);
+    bench_day::<day8::Day8>(c);
+    bench_day::<day9::Day9>(c);
+    bench_day::<day10::Day10>(c);
+    bench_day::<day11::Day11>(c);
+    bench_day::<day12::Day12>(c);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
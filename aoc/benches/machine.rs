@@ -0,0 +1,88 @@
+// The intcode machine on workloads of the days using it, to catch slowdowns
+// in the interpreter and its Io. scripts/bench-compare.sh runs it against the
+// baseline of a git revision and fails on regressions.
+use aoc_runner::Solution;
+use criterion::{criterion_group, criterion_main, Criterion};
+use intcode::*;
+
+fn load<S: Solution<Input = Vec<Word>>>() -> Vec<Word> {
+    let input = aoc_runner::Puzzle::of::<S>().input(None).unwrap();
+    S::parse(&input).unwrap()
+}
+
+// Searches the nouns and verbs by running the program, instead of solving
+// for them like day2 does.
+fn day2_brute_force(c: &mut Criterion) {
+    let prog = load::<day2::Day2>();
+    c.bench_function("day2 brute force", |b| {
+        b.iter(|| {
+            (0..100)
+                .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+                .find(|&(noun, verb)| day2::run(&prog, noun, verb).unwrap() == 19690720)
+                .unwrap()
+        })
+    });
+}
+
+fn day7_permutations(c: &mut Criterion) {
+    let prog = load::<day7::Day7>();
+    let mut group = c.benchmark_group("day7 permutations");
+    group.bench_function("serial", |b| b.iter(|| day7::Day7::part1(&prog).unwrap()));
+    group.bench_function("feedback", |b| b.iter(|| day7::Day7::part2(&prog).unwrap()));
+    group.finish();
+}
+
+// BOOST in sensor boost mode, through each way of driving the machine.
+fn day9_boost(c: &mut Criterion) {
+    let prog = load::<day9::Day9>();
+    let mut group = c.benchmark_group("day9 boost");
+    group.sample_size(20);
+    group.bench_function("run_until_blocked", |b| {
+        b.iter(|| {
+            let mut io = BufIo::new(vec![2]);
+            Machine::new(prog.clone())
+                .run_until_blocked(&mut io)
+                .unwrap();
+            io.into_output()
+        })
+    });
+    group.bench_function("step", |b| {
+        b.iter(|| {
+            let mut io = IoBuffer::with_data(&[2]);
+            let mut machine = Machine::new(prog.clone());
+            while machine.step(&mut io) == StepResult::Continue {}
+            io.into_output()
+        })
+    });
+    group.bench_function("coverage", |b| {
+        b.iter(|| {
+            let mut io = BufIo::new(vec![2]);
+            let mut coverage = Coverage::new();
+            Machine::new(prog.clone()).execute_observed(&mut io, &mut coverage);
+            coverage
+        })
+    });
+    group.finish();
+}
+
+fn day12_game(c: &mut Criterion) {
+    let prog = load::<day12::Day12>();
+    let mut group = c.benchmark_group("day12 game");
+    group.sample_size(20);
+    group.bench_function("first frame", |b| {
+        b.iter(|| day12::Day12::part1(&prog).unwrap())
+    });
+    group.bench_function("autopilot", |b| {
+        b.iter(|| day12::Day12::part2(&prog).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    day2_brute_force,
+    day7_permutations,
+    day9_boost,
+    day12_game
+);
+criterion_main!(benches);
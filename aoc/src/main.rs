@@ -0,0 +1,96 @@
+use aoc_runner::{Answers, Args, Check, DynResult, Puzzle};
+
+fn puzzles() -> Vec<Puzzle> {
+    vec![
+        Puzzle::of::<day1::Day1>(),
+        Puzzle::of::<day2::Day2>(),
+        Puzzle::of::<day3::Day3>(),
+        Puzzle::of::<day4::Day4>(),
+        Puzzle::of::<day5::Day5>(),
+        Puzzle::of::<day6::Day6>(),
+        Puzzle::of::<day7::Day7>(),
+        Puzzle::of::<day8::Day8>(),
+        Puzzle::of::<day9::Day9>(),
+        Puzzle::of::<day10::Day10>(),
+        Puzzle::of::<day11::Day11>(),
+        Puzzle::of::<day12::Day12>(),
+    ]
+}
+
+const USAGE: &str = "usage: aoc <day|all> [--part N] [--input PATH]
+       aoc check [day] [--answers PATH]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+fn select(day: Option<&str>) -> DynResult<Vec<Puzzle>> {
+    match day {
+        Some("all") | None => Ok(puzzles()),
+        Some(day) => {
+            let day = day.parse::<u32>().unwrap_or_else(|_| usage());
+            let puzzle = puzzles().into_iter().find(|puzzle| puzzle.day == day);
+            Ok(vec![
+                puzzle.ok_or_else(|| format!("Day {} is not solved", day))?
+            ])
+        }
+    }
+}
+
+// Runs the puzzles on their own inputs and compares with the recorded answers.
+fn check(args: &[String]) -> DynResult<()> {
+    let answers_path = match args.iter().position(|arg| arg == "--answers") {
+        Some(idx) => args.get(idx + 1).unwrap_or_else(|| usage()).into(),
+        None => Answers::default_path(),
+    };
+    let day = args.get(2).filter(|arg| !arg.starts_with("--"));
+    let selected = select(day.map(String::as_str))?;
+    let check = Check::run(&selected, &Answers::load(answers_path)?);
+    print!("{}", check);
+    if !check.passed() {
+        return Err("Answers differ from the recorded ones".into());
+    }
+    Ok(())
+}
+
+fn main() -> DynResult<()> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("check") => return check(&args),
+        Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        _ => {}
+    }
+    let options = Args::parse(&args)?;
+    let selected = match args.get(1) {
+        Some(day) => select(Some(day))?,
+        None => usage(),
+    };
+    if selected.len() > 1 && options.input.is_some() {
+        return Err("--input only works for a single day".into());
+    }
+
+    let single = selected.len() == 1;
+    let mut failed = Vec::new();
+    for puzzle in selected {
+        // with several days, those without the asked for part are left out
+        let solved = options.part.is_none_or(|part| part <= puzzle.parts);
+        if !single && !solved {
+            println!("Day {} has no part {}", puzzle.day, options.part.unwrap());
+            continue;
+        }
+        let input = puzzle.input(options.input.as_deref())?;
+        let report = puzzle.run(&input, &options.parts())?;
+        print!("{}", report);
+        if report.failed() {
+            failed.push(puzzle.day);
+        }
+    }
+    if !failed.is_empty() {
+        return Err(format!("Failed days: {:?}", failed).into());
+    }
+    Ok(())
+}
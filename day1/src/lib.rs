@@ -0,0 +1,46 @@
+use aoc_runner::{DynResult, Solution};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> DynResult<Vec<u32>> {
+        Ok(input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(masses: &Vec<u32>) -> DynResult<String> {
+        let total: u32 = masses.iter().map(|mass| fuel_for_mass(*mass)).sum();
+        Ok(total.to_string())
+    }
+
+    fn part2(masses: &Vec<u32>) -> DynResult<String> {
+        let total: u32 = masses
+            .iter()
+            .map(|mass| fuel_for_module_adjusted(*mass))
+            .sum();
+        Ok(total.to_string())
+    }
+}
+
+fn fuel_for_mass(mass: u32) -> u32 {
+    (mass / 3).max(2) - 2
+}
+
+fn fuel_for_module_adjusted(initial_mass: u32) -> u32 {
+    let mut total = 0;
+    let mut extra_mass = initial_mass;
+    loop {
+        extra_mass = fuel_for_mass(extra_mass);
+        total += extra_mass;
+        if extra_mass == 0 {
+            return total;
+        }
+    }
+}
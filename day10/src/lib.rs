@@ -0,0 +1,249 @@
+use aoc_runner::{DynResult, Solution};
+use grid::{Grid, Point};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    const PARTS: u8 = 1;
+    type Input = Map;
+
+    fn parse(input: &str) -> DynResult<Map> {
+        parse_map(input.as_bytes())
+    }
+
+    fn part1(map: &Map) -> DynResult<String> {
+        let best = best_location(map).ok_or("No asteroids on the map")?;
+        Ok(best.score.to_string())
+    }
+}
+
+fn parse_map(map: &[u8]) -> DynResult<Map> {
+    let cells = Grid::parse(std::str::from_utf8(map)?, |c| match c {
+        '#' => Some(Cell::Asteroid),
+        '.' => Some(Cell::Empty),
+        _ => None,
+    })?;
+    Ok(Map { cells })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Loc {
+    pos: Point,
+    score: usize,
+}
+
+fn best_location(map: &Map) -> Option<Loc> {
+    map.asteroids()
+        .map(|pos| {
+            let score = map
+                .asteroids()
+                .filter(|pos2| check_visibility(map, pos, *pos2))
+                .count();
+            Loc { pos, score }
+        })
+        .max_by_key(|l| l.score)
+}
+
+fn check_visibility(map: &Map, from: Point, to: Point) -> bool {
+    if from == to {
+        return false;
+    }
+
+    let delta = to - from;
+    let denom = gcd(delta.x as i32, delta.y as i32).max(1) as isize;
+    let step = Point::new(delta.x / denom, delta.y / denom);
+    for multiple in 1..denom {
+        if map.is_occupied(from + step * multiple) {
+            return false;
+        }
+    }
+    true
+}
+
+#[inline]
+fn gcd(mut m: i32, mut n: i32) -> i32 {
+    if m == 0 || n == 0 {
+        return (m | n).abs();
+    }
+    let shift: u32 = (m | n).trailing_zeros();
+    if m == i32::MIN || n == i32::MIN {
+        return 1 << shift;
+    }
+
+    m = m.abs();
+    n = n.abs();
+
+    n >>= n.trailing_zeros();
+    while m != 0 {
+        m >>= m.trailing_zeros();
+        if n > m {
+            std::mem::swap(&mut n, &mut m)
+        }
+        m -= n;
+    }
+    n << shift
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Asteroid,
+}
+
+#[derive(Debug)]
+pub struct Map {
+    cells: Grid<Cell>,
+}
+
+impl Map {
+    // Column by column.
+    fn positions(&self) -> impl Iterator<Item = Point> {
+        let w = self.cells.width() as isize;
+        let h = self.cells.height() as isize;
+        (0..w).flat_map(move |x| (0..h).map(move |y| Point::new(x, y)))
+    }
+
+    fn asteroids<'a>(&'a self) -> impl Iterator<Item = Point> + 'a {
+        self.positions().filter(move |pos| self.is_occupied(*pos))
+    }
+
+    fn is_occupied(&self, pos: Point) -> bool {
+        self.cells[pos] == Cell::Asteroid
+    }
+}
+
+#[test]
+fn test_example1() -> DynResult<()> {
+    let map = parse_map(
+        r#"
+        .#..#
+        .....
+        #####
+        ....#
+        ...##"#
+            .as_bytes(),
+    )?;
+    assert_eq!(
+        best_location(&map),
+        Some(Loc {
+            pos: Point::new(3, 4),
+            score: 8
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_example2() -> DynResult<()> {
+    let map = parse_map(
+        r#"
+        ......#.#.
+        #..#.#....
+        ..#######.
+        .#.#.###..
+        .#..#.....
+        ..#....#.#
+        #..#....#.
+        .##.#..###
+        ##...#..#.
+        .#....####"#
+            .as_bytes(),
+    )?;
+    assert_eq!(
+        best_location(&map),
+        Some(Loc {
+            pos: Point::new(5, 8),
+            score: 33
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_example3() -> DynResult<()> {
+    let map = parse_map(
+        r#"
+        #.#...#.#.
+        .###....#.
+        .#....#...
+        ##.#.#.#.#
+        ....#.#.#.
+        .##..###.#
+        ..#...##..
+        ..##....##
+        ......#...
+        .####.###."#
+            .as_bytes(),
+    )?;
+    assert_eq!(
+        best_location(&map),
+        Some(Loc {
+            pos: Point::new(1, 2),
+            score: 35
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_example4() -> DynResult<()> {
+    let map = parse_map(
+        r#"
+        .#..#..###
+        ####.###.#
+        ....###.#.
+        ..###.##.#
+        ##.##.#.#.
+        ....###..#
+        ..#.#..#.#
+        #..#.#.###
+        .##...##.#
+        .....#.#.."#
+            .as_bytes(),
+    )?;
+    assert_eq!(
+        best_location(&map),
+        Some(Loc {
+            pos: Point::new(6, 3),
+            score: 41
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_example5() -> DynResult<()> {
+    let map = parse_map(
+        r#"
+        .#..##.###...#######
+        ##.############..##.
+        .#.######.########.#
+        .###.#######.####.#.
+        #####.##.#.##.###.##
+        ..#####..#.#########
+        ####################
+        #.####....###.#.#.##
+        ##.#################
+        #####.##.###..####..
+        ..######..##.#######
+        ####.##.####...##..#
+        .#####..#.######.###
+        ##...#.##########...
+        #.##########.#######
+        .####.#.###.###.#.##
+        ....##.##.###..#####
+        .#.#.###########.###
+        #.#.#.#####.####.###
+        ###.##.####.##.#..##"#
+            .as_bytes(),
+    )?;
+    assert_eq!(
+        best_location(&map),
+        Some(Loc {
+            pos: Point::new(11, 13),
+            score: 210
+        })
+    );
+    Ok(())
+}
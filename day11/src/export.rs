@@ -0,0 +1,197 @@
+use grid::{Bounds, Point};
+use intcode::*;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::robot::{Action, Panels, Robot};
+
+const UNPAINTED: [u8; 3] = [0x30, 0x30, 0x38];
+const BLACK: [u8; 3] = [0x10, 0x10, 0x10];
+const WHITE: [u8; 3] = [0xf0, 0xf0, 0xf0];
+const REPAINTED_BLACK: [u8; 3] = [0x60, 0x18, 0x18];
+const REPAINTED_WHITE: [u8; 3] = [0xf0, 0xc8, 0x50];
+const PATH: [u8; 3] = [0x40, 0xa0, 0xf0];
+
+// Image of the hull, oriented like `Robot::print_drawing`, optionally marking
+// panels painted more than once and where the robot went.
+pub struct HullImage {
+    panels: Panels,
+    paints: HashMap<Point, usize>,
+    path: Vec<Point>,
+    area: Bounds,
+    scale: usize,
+    show_repainted: bool,
+    show_path: bool,
+}
+
+impl HullImage {
+    pub fn new(robot: &Robot) -> Self {
+        let mut paints = HashMap::new();
+        let mut path = vec![Point::ORIGIN];
+        for action in robot.log() {
+            match action {
+                Action::Paint { pos, .. } => *paints.entry(*pos).or_insert(0) += 1,
+                Action::Turn { to, .. } => path.push(*to),
+            }
+        }
+        let area = Bounds::of(robot.panels.points().chain(path.iter().copied())).unwrap();
+        Self {
+            panels: robot.panels.clone(),
+            paints,
+            path,
+            area,
+            scale: 8,
+            show_repainted: false,
+            show_path: false,
+        }
+    }
+
+    // Pixels per panel in the PNG, the SVG is scaled by its viewer.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn repainted(mut self, show: bool) -> Self {
+        self.show_repainted = show;
+        self
+    }
+
+    pub fn path(mut self, show: bool) -> Self {
+        self.show_path = show;
+        self
+    }
+
+    pub fn repainted_count(&self) -> usize {
+        self.paints.values().filter(|count| **count > 1).count()
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.area.width(), self.area.height())
+    }
+
+    // Column and row of the panel in the image.
+    fn cell(&self, pos: Point) -> (usize, usize) {
+        let offset = pos - self.area.min;
+        (offset.x as usize, offset.y as usize)
+    }
+
+    fn color(&self, pos: Point) -> [u8; 3] {
+        let repainted = self.show_repainted && self.paints.get(&pos).is_some_and(|n| *n > 1);
+        match (self.panels.get(pos), repainted) {
+            (None, _) => UNPAINTED,
+            (Some(false), false) => BLACK,
+            (Some(true), false) => WHITE,
+            (Some(false), true) => REPAINTED_BLACK,
+            (Some(true), true) => REPAINTED_WHITE,
+        }
+    }
+
+    // RGB rows, the path drawn as a dot in the middle of each visited panel.
+    fn pixels(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let (scale, row_len) = (self.scale, width * self.scale * 3);
+        let mut pixels = vec![0; row_len * height * scale];
+        let mut fill = |(col, row): (usize, usize), inset: usize, color: [u8; 3]| {
+            for py in row * scale + inset..(row + 1) * scale - inset {
+                for px in col * scale + inset..(col + 1) * scale - inset {
+                    pixels[py * row_len + px * 3..][..3].copy_from_slice(&color);
+                }
+            }
+        };
+        for pos in self.area.points() {
+            fill(self.cell(pos), 0, self.color(pos));
+        }
+        if self.show_path {
+            let inset = scale / 3;
+            for pos in &self.path {
+                fill(self.cell(*pos), inset, PATH);
+            }
+        }
+        pixels
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> DynResult<()> {
+        let (width, height) = self.size();
+        let file = BufWriter::new(File::create(path)?);
+        let (width, height) = ((width * self.scale) as u32, (height * self.scale) as u32);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels())?;
+        Ok(())
+    }
+
+    // A square per panel of size 1, the path as a line through their centers.
+    pub fn svg(&self) -> String {
+        let (width, height) = self.size();
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+            width,
+            height,
+            width * self.scale,
+            height * self.scale
+        );
+        for pos in self.area.points() {
+            let (col, row) = self.cell(pos);
+            let [r, g, b] = self.color(pos);
+            let _ = writeln!(
+                out,
+                r##"<rect x="{}" y="{}" width="1" height="1" fill="#{:02x}{:02x}{:02x}"/>"##,
+                col, row, r, g, b
+            );
+        }
+        if self.show_path {
+            let points: Vec<String> = self
+                .path
+                .iter()
+                .map(|pos| {
+                    let (col, row) = self.cell(*pos);
+                    format!("{}.5,{}.5", col, row)
+                })
+                .collect();
+            let [r, g, b] = PATH;
+            let _ = writeln!(
+                out,
+                r##"<polyline points="{}" fill="none" stroke="#{:02x}{:02x}{:02x}" stroke-width="0.2"/>"##,
+                points.join(" "),
+                r,
+                g,
+                b
+            );
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    pub fn save_svg(&self, path: impl AsRef<Path>) -> DynResult<()> {
+        Ok(std::fs::write(path, self.svg())?)
+    }
+}
+
+#[test]
+fn test_hull_image() {
+    // paints every panel white and turns right, forever
+    let prog = vec![3, 100, 104, 1, 104, 1, 1105, 1, 0];
+    let mut robot = Robot::new().max_moves(5);
+    robot.run(prog).unwrap();
+    let image = HullImage::new(&robot).scale(3).repainted(true).path(true);
+    assert_eq!(image.repainted_count(), 1);
+    assert_eq!(image.size(), (2, 2));
+    assert_eq!(image.color(Point::ORIGIN), REPAINTED_WHITE);
+    assert_eq!(image.color(Point::new(1, 0)), WHITE);
+
+    let pixels = image.pixels();
+    assert_eq!(pixels.len(), 6 * 6 * 3);
+    assert_eq!(pixels[..3], REPAINTED_WHITE);
+    assert_eq!(pixels[(6 + 1) * 3..][..3], PATH);
+
+    let svg = image.svg();
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert!(svg.contains(r#"points="0.5,0.5 1.5,0.5 1.5,1.5 0.5,1.5 0.5,0.5 1.5,0.5""#));
+}
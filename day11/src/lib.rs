@@ -0,0 +1,32 @@
+use aoc_runner::{DynResult, Solution};
+use grid::Point;
+use intcode::*;
+
+pub mod export;
+pub mod replay;
+pub mod robot;
+
+use robot::Robot;
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    fn part1(prog: &Vec<Word>) -> DynResult<String> {
+        let mut robot = Robot::new();
+        robot.run(prog.clone())?;
+        Ok(robot.panels.len().to_string())
+    }
+
+    fn part2(prog: &Vec<Word>) -> DynResult<String> {
+        let mut robot = Robot::with_panels(std::iter::once((Point::ORIGIN, true)).collect());
+        robot.run(prog.clone())?;
+        Ok(robot.message())
+    }
+}
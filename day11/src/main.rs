@@ -1,132 +1,75 @@
-use intcode::*;
-use std::collections::HashMap;
+use aoc_runner::{Args, DynResult, Puzzle, Solution};
+use day11::export::HullImage;
+use day11::replay;
+use day11::robot::{load_panels, Robot};
+use day11::Day11;
+use grid::Point;
+use std::time::Duration;
 
-fn main() -> DynResult<()> {
-    let prog = parse_intcode(&std::fs::read("day11-input.txt")?)?;
-
-    let mut robot = Robot::new();
-    Machine::new(prog.clone()).execute(&mut robot);
-    println!("len: {}", robot.panels.len());
-    
-    let mut robot = Robot::new();
-    robot.panels.insert((0, 0), true);
-    Machine::new(prog.clone()).execute(&mut robot);
-    robot.print_drawing();    
-
-    Ok(())
-}
+const TOOL_FLAGS: &[&str] = &["--start", "--max-moves", "--replay", "--png", "--svg"];
 
-#[derive(Clone, Copy, Debug)]
-enum Dir {
-    Up,
-    Down,
-    Right,
-    Left,
+fn arg_value(args: &[String], name: &str) -> Option<String> {
+    args.iter().skip_while(|arg| *arg != name).nth(1).cloned()
 }
 
-impl Dir {
-    fn turn_left(self) -> Self {
-        match self {
-            Dir::Up => Dir::Left,
-            Dir::Down => Dir::Right,
-            Dir::Right => Dir::Up,
-            Dir::Left => Dir::Down,
-        }
-    }
-    
-    fn turn_right(self) -> Self {
-        match self {
-            Dir::Up => Dir::Right,
-            Dir::Down => Dir::Left,
-            Dir::Right => Dir::Down,
-            Dir::Left => Dir::Up,
-        }
+// Prints the answers, unless asked to run a robot differently or to show how
+// it painted.
+fn main() -> DynResult<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|arg| TOOL_FLAGS.contains(&arg.as_str())) {
+        return aoc_runner::main::<Day11>();
     }
-}
+    let input = Puzzle::of::<Day11>().input(Args::parse(&args)?.input.as_deref())?;
+    let prog = Day11::parse(&input)?;
 
-#[derive(Clone, Copy, Debug)]
-enum RobotState {
-    Paint,
-    Turn,
-}
+    let max_moves = match arg_value(&args, "--max-moves") {
+        Some(moves) => Some(moves.parse::<usize>()?),
+        None => None,
+    };
+    let delay = match arg_value(&args, "--delay") {
+        Some(ms) => Duration::from_millis(ms.parse()?),
+        None => Duration::from_millis(20),
+    };
+    let new_robot = |robot: Robot| match max_moves {
+        Some(max_moves) => robot.max_moves(max_moves),
+        None => robot,
+    };
 
-struct Robot {
-    pos: (isize, isize),
-    dir: Dir,
-    state: RobotState,
-    panels: HashMap<(isize, isize), bool>,
-}
+    // Without `--start` the robot starts on a white panel, as in part 2.
+    let mut robot = match arg_value(&args, "--start") {
+        Some(path) => new_robot(Robot::with_panels(load_panels(path)?)),
+        None => new_robot(Robot::with_panels(
+            std::iter::once((Point::ORIGIN, true)).collect(),
+        )),
+    };
+    robot.run(prog)?;
+    println!("len: {}", robot.panels.len());
 
-impl Robot {
-    fn new() -> Self {
-        Self {
-            pos: (0, 0),
-            dir: Dir::Up,
-            state: RobotState::Paint,
-            panels: HashMap::new(),
-        }
+    if args.iter().any(|arg| arg == "--replay") {
+        replay::replay(robot.start(), robot.log(), delay)?;
+    } else {
+        robot.print_drawing();
     }
+    println!("message: {}", robot.message());
+    println!("moves: {}", robot.moves());
 
-    fn print_drawing(&self) {
-        let (minx, maxx, miny, maxy) = self.panels.keys().fold(
-            (isize::max_value(),
-            isize::min_value(),
-            isize::max_value(),
-            isize::min_value()), |(minx, maxx, miny, maxy), (x, y)| {
-                (
-                    minx.min(*x),
-                    maxx.max(*x),
-                    miny.min(*y),
-                    maxy.max(*y),
-                )
-            }
-        );
-    
-        for y in (miny..=maxy).rev() {
-            for x in minx..=maxx {
-                let c = match self.panels.get(&(x, y)) {
-                    Some(true) => "##",
-                    Some(false) => ". ",
-                    None => "  ",
-                };
-                print!("{}", c);
-            }
-            print!("\n");
+    let (png, svg) = (arg_value(&args, "--png"), arg_value(&args, "--svg"));
+    if png.is_some() || svg.is_some() {
+        let mut image = HullImage::new(&robot)
+            .repainted(args.iter().any(|arg| arg == "--highlight-repainted"))
+            .path(args.iter().any(|arg| arg == "--highlight-path"));
+        if let Some(scale) = arg_value(&args, "--scale") {
+            image = image.scale(scale.parse()?);
+        }
+        println!("repainted: {}", image.repainted_count());
+        for path in png.iter() {
+            image.save_png(path)?;
+            println!("Saved {}", path);
+        }
+        for path in svg.iter() {
+            image.save_svg(path)?;
+            println!("Saved {}", path);
         }
     }
-}
-
-impl Io for Robot {
-    fn read_in(&mut self) -> Option<Word> {
-        Some(self.panels.get(&self.pos).cloned().unwrap_or(false) as _)
-    }
-
-    fn write_out(&mut self, data: Word) -> bool {
-        match self.state {
-            RobotState::Paint => {
-                match data {
-                    0 => self.panels.insert(self.pos, false),
-                    1 => self.panels.insert(self.pos, true),
-                    other => panic!("Illegal paint: {}", other),
-                };
-                self.state = RobotState::Turn;
-            }
-            RobotState::Turn => {
-                match data {
-                    0 => self.dir = self.dir.turn_left(),
-                    1 => self.dir = self.dir.turn_right(),
-                    other => panic!("Illegal turn: {}", other),
-                }
-                match self.dir {
-                    Dir::Up => self.pos.1 += 1,
-                    Dir::Down => self.pos.1 -= 1,
-                    Dir::Right => self.pos.0 += 1,
-                    Dir::Left => self.pos.0 -= 1,
-                }
-
-                self.state = RobotState::Paint;
-            }
-        };
-        true
-    }
+    Ok(())
 }
@@ -0,0 +1,53 @@
+use grid::{Bounds, Dir, Point};
+use intcode::*;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::robot::{drawing_within, Action, Panels};
+
+// Animates the actions in the terminal, starting from the `start` panels. A
+// frame is shown after each turn, all of them covering the whole area the
+// robot ever gets to.
+pub fn replay(start: &Panels, log: &[Action], delay: Duration) -> DynResult<()> {
+    let mut panels = start.clone();
+    let mut robot = (Point::ORIGIN, Dir::Up);
+    let visited = log.iter().map(|action| match action {
+        Action::Paint { pos, .. } | Action::Turn { to: pos, .. } => *pos,
+    });
+    let area = Bounds::of(start.points().chain(visited).chain(Some(robot.0))).unwrap();
+    let mut stdout = std::io::stdout();
+    // clear once, then only move the cursor home to avoid flicker
+    print!("\x1b[2J");
+    let total = log.len();
+    for (idx, action) in log.iter().enumerate() {
+        match *action {
+            Action::Paint { pos, white } => {
+                panels.insert(pos, white);
+            }
+            Action::Turn { dir, to } => {
+                robot = (to, dir);
+                show(&mut stdout, &panels, robot, area, idx + 1, total)?;
+                std::thread::sleep(delay);
+            }
+        }
+    }
+    show(&mut stdout, &panels, robot, area, total, total)
+}
+
+fn show(
+    out: &mut impl Write,
+    panels: &Panels,
+    robot: (Point, Dir),
+    area: Bounds,
+    done: usize,
+    total: usize,
+) -> DynResult<()> {
+    write!(
+        out,
+        "\x1b[H{}",
+        drawing_within(panels, Some(robot), area).replace('\n', "\x1b[K\n")
+    )?;
+    writeln!(out, "action {} of {}\x1b[K\x1b[J", done, total)?;
+    out.flush()?;
+    Ok(())
+}
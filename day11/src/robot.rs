@@ -0,0 +1,217 @@
+use grid::{Bounds, Dir, Point, SparseGrid};
+use intcode::*;
+use std::path::Path;
+
+pub type Panels = SparseGrid<bool>;
+
+fn glyph(dir: Dir) -> &'static str {
+    match dir {
+        Dir::Up => "^^",
+        Dir::Down => "vv",
+        Dir::Right => ">>",
+        Dir::Left => "<<",
+    }
+}
+
+// Everything the robot did, in order. A turn is always followed by moving one
+// panel forward, to `to`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Paint { pos: Point, white: bool },
+    Turn { dir: Dir, to: Point },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum RobotState {
+    Paint,
+    Turn,
+}
+
+pub struct Robot {
+    pos: Point,
+    dir: Dir,
+    state: RobotState,
+    pub panels: Panels,
+    start: Panels,
+    log: Vec<Action>,
+    max_moves: Option<usize>,
+    moves: usize,
+}
+
+impl Robot {
+    pub fn new() -> Self {
+        Self::with_panels(Panels::new())
+    }
+
+    pub fn with_panels(panels: Panels) -> Self {
+        Self {
+            pos: Point::ORIGIN,
+            dir: Dir::Up,
+            state: RobotState::Paint,
+            start: panels.clone(),
+            panels,
+            log: Vec::new(),
+            max_moves: None,
+            moves: 0,
+        }
+    }
+
+    // The robot stops asking for the panel color after `max_moves` moves, which
+    // blocks the program.
+    pub fn max_moves(mut self, max_moves: usize) -> Self {
+        self.max_moves = Some(max_moves);
+        self
+    }
+
+    // Runs the robot program until it halts or the robot ran out of moves.
+    pub fn run(&mut self, prog: Vec<Word>) -> DynResult<()> {
+        Machine::new(prog).run_until_blocked(self)?;
+        Ok(())
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    pub fn start(&self) -> &Panels {
+        &self.start
+    }
+
+    pub fn log(&self) -> &[Action] {
+        &self.log
+    }
+
+    // The white panels read as text.
+    pub fn message(&self) -> String {
+        let white = self.panels.iter().filter(|(_, w)| **w).map(|(p, _)| p);
+        let bounds = match Bounds::of(white) {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let rows: Vec<Vec<bool>> = (bounds.min.y..=bounds.max.y)
+            .map(|y| {
+                (bounds.min.x..=bounds.max.x)
+                    .map(|x| self.panels.get(Point::new(x, y)) == Some(&true))
+                    .collect()
+            })
+            .collect();
+        ocr::recognize(&rows)
+    }
+
+    pub fn print_drawing(&self) {
+        print!("{}", drawing(&self.panels, None));
+    }
+}
+
+impl Default for Robot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Renders the panels two characters wide, the robot optionally drawn on top
+// of them.
+pub fn drawing(panels: &Panels, robot: Option<(Point, Dir)>) -> String {
+    let points = panels.points().chain(robot.map(|(pos, _)| pos));
+    match Bounds::of(points) {
+        Some(bounds) => drawing_within(panels, robot, bounds),
+        None => String::new(),
+    }
+}
+
+pub fn drawing_within(panels: &Panels, robot: Option<(Point, Dir)>, bounds: Bounds) -> String {
+    panels.render_within(bounds, |pos, panel| match (robot, panel) {
+        (Some((robot, dir)), _) if robot == pos => glyph(dir),
+        (_, Some(true)) => "##",
+        (_, Some(false)) => ". ",
+        (_, None) => "  ",
+    })
+}
+
+// One character per panel, `#` white, `.` black and anything else unpainted.
+// The first character of the first line is where the robot starts.
+pub fn parse_panels(text: &str) -> Panels {
+    SparseGrid::parse(text, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+}
+
+pub fn load_panels(path: impl AsRef<Path>) -> DynResult<Panels> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read panels {}: {}", path.display(), e))?;
+    Ok(parse_panels(&text))
+}
+
+impl Io for Robot {
+    fn read_in(&mut self) -> Option<Word> {
+        if self.max_moves.is_some_and(|max| self.moves >= max) {
+            return None;
+        }
+        Some(self.panels.get(self.pos).cloned().unwrap_or(false) as _)
+    }
+
+    fn write_out(&mut self, data: Word) -> bool {
+        match self.state {
+            RobotState::Paint => {
+                let white = match data {
+                    0 => false,
+                    1 => true,
+                    other => panic!("Illegal paint: {}", other),
+                };
+                self.panels.insert(self.pos, white);
+                self.log.push(Action::Paint {
+                    pos: self.pos,
+                    white,
+                });
+                self.state = RobotState::Turn;
+            }
+            RobotState::Turn => {
+                match data {
+                    0 => self.dir = self.dir.turn_left(),
+                    1 => self.dir = self.dir.turn_right(),
+                    other => panic!("Illegal turn: {}", other),
+                }
+                self.pos = self.pos.step(self.dir);
+                self.moves += 1;
+                self.log.push(Action::Turn {
+                    dir: self.dir,
+                    to: self.pos,
+                });
+
+                self.state = RobotState::Paint;
+            }
+        };
+        true
+    }
+}
+
+#[test]
+fn test_max_moves_and_log() {
+    // paints every panel white and turns right, forever
+    let prog = vec![3, 100, 104, 1, 104, 1, 1105, 1, 0];
+    let mut robot = Robot::with_panels(parse_panels(".#\n #")).max_moves(3);
+    robot.run(prog).unwrap();
+    assert_eq!(robot.moves(), 3);
+    assert_eq!(robot.start().len(), 3);
+    assert_eq!(
+        robot.log()[..2],
+        [
+            Action::Paint {
+                pos: Point::ORIGIN,
+                white: true
+            },
+            Action::Turn {
+                dir: Dir::Right,
+                to: Point::new(1, 0)
+            },
+        ]
+    );
+    assert_eq!(robot.log().len(), 6);
+    assert_eq!(
+        drawing(&robot.panels, Some((Point::ORIGIN, Dir::Up))),
+        "^^##\n  ##\n"
+    );
+}
@@ -0,0 +1,234 @@
+use intcode::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::game::{Event, Game};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move(Word),
+    // Go back the given number of frames, if the arcade keeps a history.
+    Rewind(usize),
+    Stop,
+}
+
+pub trait Controller {
+    fn action(&mut self, game: &Game) -> DynResult<Action>;
+
+    // Asked once the program halted, while there is history to rewind into.
+    fn game_over(&mut self, _game: &Game) -> DynResult<Action> {
+        Ok(Action::Stop)
+    }
+}
+
+pub trait Listener {
+    fn on_event(&mut self, event: &Event, game: &Game);
+}
+
+// Plays with each controller in turn, moving on when one stops.
+pub struct Chain<'a> {
+    controllers: VecDeque<&'a mut dyn Controller>,
+}
+
+impl<'a> Chain<'a> {
+    pub fn new(controllers: Vec<&'a mut dyn Controller>) -> Self {
+        Self {
+            controllers: controllers.into(),
+        }
+    }
+}
+
+impl Controller for Chain<'_> {
+    fn action(&mut self, game: &Game) -> DynResult<Action> {
+        while let Some(controller) = self.controllers.front_mut() {
+            match controller.action(game)? {
+                Action::Stop => self.controllers.pop_front(),
+                action => return Ok(action),
+            };
+        }
+        Ok(Action::Stop)
+    }
+
+    fn game_over(&mut self, game: &Game) -> DynResult<Action> {
+        for controller in &mut self.controllers {
+            match controller.game_over(game)? {
+                Action::Stop => {}
+                action => return Ok(action),
+            }
+        }
+        Ok(Action::Stop)
+    }
+}
+
+pub struct Arcade {
+    pub machine: Machine,
+    pub game: Game,
+    halted: bool,
+    // state before each of the last `rewind_limit` frames
+    history: VecDeque<(Machine, Game)>,
+    rewind_limit: usize,
+    listeners: Vec<Rc<RefCell<dyn Listener>>>,
+}
+
+impl Arcade {
+    pub fn new(prog: Vec<Word>) -> Self {
+        Self::with_rewind(prog, 0)
+    }
+
+    pub fn with_rewind(prog: Vec<Word>, rewind_limit: usize) -> Self {
+        Self {
+            machine: Machine::new(prog),
+            game: Game::new(),
+            halted: false,
+            history: VecDeque::new(),
+            rewind_limit,
+            listeners: Vec::new(),
+        }
+    }
+
+    // Returns the shared handle, so the listener can be inspected later.
+    pub fn subscribe<L: Listener + 'static>(&mut self, listener: L) -> Rc<RefCell<L>> {
+        let listener = Rc::new(RefCell::new(listener));
+        self.listeners.push(listener.clone());
+        listener
+    }
+
+    fn emit(&mut self, events: Vec<Event>) {
+        for event in &events {
+            for listener in &self.listeners {
+                listener.borrow_mut().on_event(event, &self.game);
+            }
+        }
+    }
+
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    // Runs the program until it asks for the joystick again or halts. The
+    // first call is expected without a joystick position, to draw the start.
+    pub fn frame(&mut self, joystick: Option<Word>) -> DynResult<()> {
+        if let Some(joystick) = joystick {
+            if self.rewind_limit > 0 {
+                if self.history.len() == self.rewind_limit {
+                    self.history.pop_front();
+                }
+                self.history
+                    .push_back((self.machine.clone(), self.game.clone()));
+            }
+            self.game.set_joystick(joystick);
+        }
+        self.halted = self.machine.run_until_blocked(&mut self.game)? == StepResult::Halt;
+        if let Some(error) = self.game.take_error() {
+            return Err(format!("{}. IP: {}", error, self.machine.ip()).into());
+        }
+        let mut events = self.game.take_events();
+        if !self.halted {
+            events.push(Event::FrameCompleted {
+                frame: self.game.frames,
+            });
+        }
+        self.emit(events);
+        Ok(())
+    }
+
+    // Restores the state from up to `frames` frames ago, returns how far it
+    // actually went back.
+    pub fn rewind(&mut self, frames: usize) -> usize {
+        let frames = frames.min(self.history.len());
+        if frames > 0 {
+            self.history.truncate(self.history.len() - frames + 1);
+            let (machine, game) = self.history.pop_back().unwrap();
+            self.machine = machine;
+            self.game = game;
+            self.halted = false;
+            let frame = self.game.frames;
+            self.emit(vec![Event::Rewound { frame }]);
+        }
+        frames
+    }
+
+    // Plays until the program halts or the controller stops.
+    pub fn run(&mut self, controller: &mut dyn Controller) -> DynResult<()> {
+        if self.machine.cycles() == 0 {
+            self.frame(None)?;
+        }
+        loop {
+            let action = if !self.halted {
+                controller.action(&self.game)?
+            } else if !self.history.is_empty() {
+                controller.game_over(&self.game)?
+            } else {
+                Action::Stop
+            };
+            match action {
+                Action::Move(joystick) if !self.halted => self.frame(Some(joystick))?,
+                Action::Rewind(frames) => {
+                    self.rewind(frames);
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_rewind() {
+    // adds every joystick input to the score
+    let prog = vec![3, 50, 1, 50, 51, 51, 104, -1, 104, 0, 4, 51, 1105, 1, 0];
+    let mut arcade = Arcade::with_rewind(prog, 2);
+    arcade.frame(None).unwrap();
+    for joystick in &[5, 3, 2] {
+        arcade.frame(Some(*joystick)).unwrap();
+    }
+    assert_eq!(arcade.game.score, 10);
+
+    assert_eq!(arcade.rewind(1), 1);
+    assert_eq!((arcade.game.score, arcade.game.frames), (8, 2));
+    assert_eq!(arcade.rewind(5), 1);
+    assert_eq!((arcade.game.score, arcade.game.frames), (5, 1));
+    arcade.frame(Some(1)).unwrap();
+    assert_eq!(arcade.game.score, 6);
+}
+
+#[test]
+fn test_events_and_errors() {
+    use crate::game::Tile;
+
+    #[derive(Default)]
+    struct Log(Vec<Event>);
+
+    impl Listener for Log {
+        fn on_event(&mut self, event: &Event, _game: &Game) {
+            self.0.push(event.clone());
+        }
+    }
+
+    // draws a ball far out, sets the score, waits for input and then draws an
+    // unknown tile
+    let prog = vec![
+        104, 70, 104, 80, 104, 4, 104, -1, 104, 0, 104, 7, 3, 100, 104, 1, 104, 1, 104, 9, 99,
+    ];
+    let mut arcade = Arcade::new(prog);
+    let log = arcade.subscribe(Log::default());
+    arcade.frame(None).unwrap();
+    assert_eq!(arcade.game.size(), (71, 81));
+    assert_eq!(
+        log.borrow().0,
+        &[
+            Event::TileChanged {
+                x: 70,
+                y: 80,
+                old: Tile::Empty,
+                new: Tile::Ball
+            },
+            Event::ScoreChanged { old: 0, new: 7 },
+            Event::FrameCompleted { frame: 0 },
+        ]
+    );
+
+    let error = arcade.frame(Some(0)).unwrap_err();
+    assert!(error.to_string().starts_with("Unknown tile 9 at 1, 1"));
+}
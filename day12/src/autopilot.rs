@@ -0,0 +1,17 @@
+use intcode::*;
+
+use crate::arcade::{Action, Controller};
+use crate::game::Game;
+
+// Keeps the paddle under the ball, which is enough to clear any board.
+pub struct Autopilot;
+
+impl Controller for Autopilot {
+    fn action(&mut self, game: &Game) -> DynResult<Action> {
+        let joystick = match (game.ball(), game.paddle()) {
+            (Some((ball, _)), Some((paddle, _))) => (ball as Word - paddle as Word).signum(),
+            _ => 0,
+        };
+        Ok(Action::Move(joystick))
+    }
+}
@@ -0,0 +1,95 @@
+use grid::Grid;
+use intcode::*;
+use std::path::Path;
+
+use crate::arcade::Arcade;
+use crate::game::{Game, Tile};
+
+// The board as drawn when the program asks for the joystick.
+pub type Observation = Grid<Tile>;
+
+// Headless cabinet to script controllers against: every step plays one frame
+// and reports how the score changed. Losing the ball makes the program reset
+// the score to 0 before halting, which shows up as a negative delta.
+pub struct Environment {
+    prog: Vec<Word>,
+    // None until the first reset
+    arcade: Option<Arcade>,
+}
+
+impl Environment {
+    pub fn new(prog: Vec<Word>) -> Self {
+        Self { prog, arcade: None }
+    }
+
+    // Loads a puzzle input with quarters inserted, i.e. in free play mode.
+    pub fn from_file(path: impl AsRef<Path>) -> DynResult<Self> {
+        let mut prog = parse_intcode(&std::fs::read(path)?)?;
+        prog[0] = 2;
+        Ok(Self::new(prog))
+    }
+
+    // Starts a new game and returns the initial board.
+    pub fn reset(&mut self) -> DynResult<Observation> {
+        let mut arcade = Arcade::new(self.prog.clone());
+        arcade.frame(None)?;
+        Ok(Self::observation(&self.arcade.insert(arcade).game))
+    }
+
+    pub fn step(&mut self, joystick: Word) -> DynResult<(Observation, i64, bool)> {
+        let arcade = match &mut self.arcade {
+            Some(arcade) => arcade,
+            None => return Err("Environment stepped before reset".into()),
+        };
+        if arcade.halted() {
+            return Err("Game is over, reset it first".into());
+        }
+        if !(-1..=1).contains(&joystick) {
+            return Err(format!("Invalid joystick position {}", joystick).into());
+        }
+        let score = arcade.game.score as i64;
+        arcade.frame(Some(joystick))?;
+        let delta = arcade.game.score as i64 - score;
+        Ok((Self::observation(&arcade.game), delta, arcade.halted()))
+    }
+
+    // The full state, for ball and paddle positions, score or frame count.
+    pub fn game(&self) -> Option<&Game> {
+        self.arcade.as_ref().map(|arcade| &arcade.game)
+    }
+
+    fn observation(game: &Game) -> Observation {
+        game.board().clone()
+    }
+}
+
+#[test]
+fn test_environment() {
+    // draws a wall, then for every joystick input adds it to the score and
+    // draws the paddle one right of the input, halts once the score is 2
+    let prog = vec![
+        104, 0, 104, 0, 104, 1, 3, 60, 1, 60, 61, 61, 104, -1, 104, 0, 4, 61, 1001, 60, 1, 62, 4,
+        62, 104, 1, 104, 3, 1008, 61, 2, 63, 1006, 63, 6, 99,
+    ];
+    let board = |text: &str| {
+        Grid::parse(text, |c| match c {
+            '.' => Some(Tile::Empty),
+            '#' => Some(Tile::Wall),
+            '-' => Some(Tile::HorizontalPaddle),
+            _ => None,
+        })
+        .unwrap()
+    };
+    let mut env = Environment::new(prog);
+    assert!(env.step(1).is_err());
+    assert_eq!(env.reset().unwrap(), board("#"));
+    assert_eq!(env.step(1).unwrap(), (board("#..\n..-"), 1, false));
+    assert_eq!(env.step(0).unwrap(), (board("#..\n.--"), 0, false));
+    assert!(env.step(2).is_err());
+    assert_eq!(env.step(1).unwrap(), (board("#..\n.--"), 1, true));
+    assert_eq!(env.game().unwrap().score, 2);
+    assert!(env.step(1).is_err());
+
+    assert_eq!(env.reset().unwrap(), board("#"));
+    assert_eq!(env.step(1).unwrap(), (board("#..\n..-"), 1, false));
+}
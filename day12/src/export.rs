@@ -0,0 +1,216 @@
+use intcode::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::arcade::Listener;
+use crate::game::{Event, Game, Tile};
+
+// Pixels per tile, and per dot of the score digits.
+const SCALE: usize = 6;
+const FONT_SCALE: usize = 2;
+const PANEL_HEIGHT: usize = 5 * FONT_SCALE + 2 * SCALE;
+// In hundredths of a second, as gif wants it.
+const GIF_DELAY: u16 = 3;
+
+// Palette indices, a color for each tile in the order of its id.
+const PALETTE: [[u8; 3]; 6] = [
+    [0x10, 0x10, 0x18], // Empty " "
+    [0x80, 0x80, 0x88], // Wall "#"
+    [0xe0, 0x70, 0x30], // Block "█"
+    [0x40, 0xa0, 0xf0], // HorizontalPaddle "-"
+    [0xf0, 0xf0, 0xf0], // Ball "o"
+    [0xf0, 0xd0, 0x40], // score text
+];
+const TEXT: u8 = 5;
+
+// 3x5 digits, a row per 3 bits.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+struct Snapshot {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    score: usize,
+}
+
+// Keeps the board of every frame when the program asks for the joystick, and
+// renders them once the game is over. The image size is taken from the
+// largest board seen.
+#[derive(Default)]
+pub struct FrameCapture {
+    frames: Vec<Snapshot>,
+}
+
+impl Listener for FrameCapture {
+    fn on_event(&mut self, event: &Event, game: &Game) {
+        match event {
+            Event::FrameCompleted { .. } => self.capture(game),
+            // frames past the one the game went back to are gone, that one
+            // was captured when it first completed
+            Event::Rewound { frame } => self.frames.truncate(*frame + 1),
+            _ => {}
+        }
+    }
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Adds the current state, e.g. the final one after the program halted.
+    pub fn capture(&mut self, game: &Game) {
+        let (width, height) = game.size();
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| game.tile(x, y)))
+            .collect();
+        self.frames.push(Snapshot {
+            width,
+            height,
+            tiles,
+            score: game.score,
+        });
+    }
+
+    fn image_size(&self) -> (usize, usize) {
+        let width = self.frames.iter().map(|f| f.width).max().unwrap_or(0);
+        let height = self.frames.iter().map(|f| f.height).max().unwrap_or(0);
+        let digits = self.frames.iter().map(|f| f.score.to_string().len()).max();
+        let text_width = SCALE + digits.unwrap_or(1) * 4 * FONT_SCALE;
+        (
+            (width * SCALE).max(text_width),
+            height * SCALE + PANEL_HEIGHT,
+        )
+    }
+
+    // Palette indices of the frame, the score below the board.
+    fn render(frame: &Snapshot, (width, height): (usize, usize)) -> Vec<u8> {
+        let mut pixels = vec![Tile::Empty as u8; width * height];
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let tile = frame.tiles[y * frame.width + x] as u8;
+                fill(&mut pixels, width, x * SCALE, y * SCALE, SCALE, tile);
+            }
+        }
+        let top = frame.height * SCALE + SCALE;
+        for (idx, digit) in frame.score.to_string().bytes().enumerate() {
+            let left = SCALE + idx * 4 * FONT_SCALE;
+            for (row, bits) in DIGITS[(digit - b'0') as usize].iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let (px, py) = (left + col * FONT_SCALE, top + row * FONT_SCALE);
+                        fill(&mut pixels, width, px, py, FONT_SCALE, TEXT);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+
+    // Writes frame-00000.png and onwards into `dir`.
+    pub fn save_png_sequence(&self, dir: impl AsRef<Path>) -> DynResult<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let size = self.image_size();
+        let (width, height) = size;
+        for (idx, frame) in self.frames.iter().enumerate() {
+            let file = File::create(dir.join(format!("frame-{:05}.png", idx)))?;
+            let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(PALETTE.concat());
+            encoder
+                .write_header()?
+                .write_image_data(&Self::render(frame, size))?;
+        }
+        Ok(())
+    }
+
+    pub fn save_gif(&self, path: impl AsRef<Path>) -> DynResult<()> {
+        let size = self.image_size();
+        let (width, height) = size;
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(
+                format!("Board of {}x{} pixels is too big for a gif", width, height).into(),
+            );
+        }
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, width as u16, height as u16, &PALETTE.concat())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for frame in &self.frames {
+            let pixels = Self::render(frame, size);
+            let mut frame =
+                gif::Frame::from_indexed_pixels(width as u16, height as u16, pixels, None);
+            frame.delay = GIF_DELAY;
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+}
+
+fn fill(pixels: &mut [u8], width: usize, left: usize, top: usize, size: usize, color: u8) {
+    for y in top..top + size {
+        pixels[y * width + left..y * width + left + size].fill(color);
+    }
+}
+
+#[test]
+fn test_render_frame() {
+    // draws a wall and a ball, sets the score to 10, then waits for input
+    let prog = vec![
+        104, 0, 104, 0, 104, 1, 104, 1, 104, 0, 104, 4, 104, -1, 104, 0, 104, 10, 3, 50, 99,
+    ];
+    let mut arcade = crate::arcade::Arcade::new(prog);
+    let capture = arcade.subscribe(FrameCapture::new());
+    arcade.frame(None).unwrap();
+    let capture = capture.borrow();
+    assert_eq!(capture.frames(), 1);
+    let width = SCALE + 2 * 4 * FONT_SCALE;
+    assert_eq!(capture.image_size(), (width, SCALE + PANEL_HEIGHT));
+
+    let pixels = FrameCapture::render(&capture.frames[0], capture.image_size());
+    assert_eq!(pixels[0], Tile::Wall as u8);
+    assert_eq!(
+        pixels[(SCALE - 1) * width + 2 * SCALE - 1],
+        Tile::Ball as u8
+    );
+    assert_eq!(pixels[2 * SCALE], Tile::Empty as u8);
+    // top left dot of the "1"
+    let (x, y) = (SCALE + FONT_SCALE, 2 * SCALE);
+    assert_eq!(pixels[y * width + x], TEXT);
+    assert_eq!(pixels[y * width + x - 1], Tile::Empty as u8);
+}
+
+#[test]
+fn test_capture_rewind() {
+    // adds every joystick input to the score
+    let prog = vec![3, 50, 1, 50, 51, 51, 104, -1, 104, 0, 4, 51, 1105, 1, 0];
+    let mut arcade = crate::arcade::Arcade::with_rewind(prog, 2);
+    let capture = arcade.subscribe(FrameCapture::new());
+    arcade.frame(None).unwrap();
+    arcade.frame(Some(5)).unwrap();
+    arcade.frame(Some(3)).unwrap();
+    assert_eq!(capture.borrow().frames(), 3);
+
+    assert_eq!(arcade.rewind(1), 1);
+    assert_eq!(capture.borrow().frames(), 2);
+    arcade.frame(Some(1)).unwrap();
+    let scores: Vec<usize> = capture.borrow().frames.iter().map(|f| f.score).collect();
+    assert_eq!(scores, &[0, 5, 6]);
+}
@@ -0,0 +1,198 @@
+use grid::{Grid, Point};
+use intcode::*;
+use std::collections::VecDeque;
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tile {
+    Empty = 0,
+    Wall = 1,
+    Block = 2,
+    HorizontalPaddle = 3,
+    Ball = 4,
+}
+
+impl Tile {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Tile::Empty => " ",
+            Tile::Wall => "#",
+            Tile::Block => "█",
+            Tile::HorizontalPaddle => "-",
+            Tile::Ball => "o",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    TileChanged {
+        x: usize,
+        y: usize,
+        old: Tile,
+        new: Tile,
+    },
+    ScoreChanged {
+        old: usize,
+        new: usize,
+    },
+    // The program asks for the joystick position of the next frame.
+    FrameCompleted {
+        frame: usize,
+    },
+    // The arcade went back in time, the game is now at `frame`.
+    Rewound {
+        frame: usize,
+    },
+}
+
+// State of the cabinet as drawn by the program. As Io it hands out the
+// joystick position set by `set_joystick` once, and blocks until the next one.
+// The board grows to whatever the program draws.
+#[derive(Clone)]
+pub struct Game {
+    board: Grid<Tile>,
+    pub score: usize,
+    pub frames: usize,
+    io_buffer: VecDeque<Word>,
+    joystick: Option<Word>,
+    ball: Option<(usize, usize)>,
+    paddle: Option<(usize, usize)>,
+    events: Vec<Event>,
+    error: Option<String>,
+}
+
+impl Io for Game {
+    fn read_in(&mut self) -> Option<Word> {
+        let joystick = self.joystick.take()?;
+        self.frames += 1;
+        Some(joystick)
+    }
+
+    // Invalid output blocks the program, `take_error` tells why.
+    fn write_out(&mut self, data: Word) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        self.io_buffer.push_back(data);
+        if self.io_buffer.len() < 3 {
+            return true;
+        }
+        let a = self.io_buffer.pop_front().unwrap();
+        let b = self.io_buffer.pop_front().unwrap();
+        let c = self.io_buffer.pop_front().unwrap();
+        match self.apply(a, b, c) {
+            Ok(()) => true,
+            Err(error) => {
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self {
+            board: Grid::default(),
+            score: 0,
+            frames: 0,
+            io_buffer: VecDeque::new(),
+            joystick: None,
+            ball: None,
+            paddle: None,
+            events: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn apply(&mut self, a: Word, b: Word, c: Word) -> Result<(), String> {
+        if a == -1 && b == 0 {
+            if c < 0 {
+                return Err(format!("Negative score {}", c));
+            }
+            let old = std::mem::replace(&mut self.score, c as usize);
+            if old != self.score {
+                self.events.push(Event::ScoreChanged {
+                    old,
+                    new: self.score,
+                });
+            }
+            return Ok(());
+        }
+
+        if a < 0 || b < 0 {
+            return Err(format!("Tile drawn at negative position {}, {}", a, b));
+        }
+        let tile = match c {
+            0 => Tile::Empty,
+            1 => Tile::Wall,
+            2 => Tile::Block,
+            3 => Tile::HorizontalPaddle,
+            4 => Tile::Ball,
+            t => return Err(format!("Unknown tile {} at {}, {}", t, a, b)),
+        };
+        let pos = Point::new(a as isize, b as isize);
+        self.board.grow_to(pos, Tile::Empty);
+        let (x, y) = (a as usize, b as usize);
+
+        match tile {
+            Tile::Ball => self.ball = Some((x, y)),
+            Tile::HorizontalPaddle => self.paddle = Some((x, y)),
+            _ => {}
+        }
+        let old = std::mem::replace(&mut self.board[pos], tile);
+        if old != tile {
+            self.events.push(Event::TileChanged {
+                x,
+                y,
+                old,
+                new: tile,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn ball(&self) -> Option<(usize, usize)> {
+        self.ball
+    }
+
+    pub fn paddle(&self) -> Option<(usize, usize)> {
+        self.paddle
+    }
+
+    pub fn set_joystick(&mut self, joystick: Word) {
+        self.joystick = Some(joystick);
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.board.width(), self.board.height())
+    }
+
+    pub fn board(&self) -> &Grid<Tile> {
+        &self.board
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        let pos = Point::new(x as isize, y as isize);
+        self.board.get(pos).copied().unwrap_or(Tile::Empty)
+    }
+
+    pub fn count_tile(&self, tile: Tile) -> usize {
+        self.board.cells().iter().filter(|t| **t == tile).count()
+    }
+}
@@ -0,0 +1,45 @@
+use aoc_runner::{DynResult, Solution};
+use intcode::*;
+
+pub mod arcade;
+pub mod autopilot;
+pub mod env;
+pub mod export;
+pub mod game;
+pub mod movie;
+pub mod tui;
+
+use arcade::Arcade;
+use autopilot::Autopilot;
+use game::Tile;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    fn part1(prog: &Vec<Word>) -> DynResult<String> {
+        let mut arcade = Arcade::new(prog.clone());
+        arcade.frame(None)?;
+        Ok(arcade.game.count_tile(Tile::Block).to_string())
+    }
+
+    // The autopilot never misses, so its final score is the one after
+    // breaking all blocks.
+    fn part2(prog: &Vec<Word>) -> DynResult<String> {
+        let mut prog = prog.clone();
+        prog[0] = 2;
+        let mut arcade = Arcade::new(prog);
+        arcade.run(&mut Autopilot)?;
+        let blocks = arcade.game.count_tile(Tile::Block);
+        if blocks > 0 {
+            return Err(format!("Game ended with {} blocks left", blocks).into());
+        }
+        Ok(arcade.game.score.to_string())
+    }
+}
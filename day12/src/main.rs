@@ -1,155 +1,183 @@
+use aoc_runner::{Args, DynResult, Puzzle, Solution};
 use intcode::*;
-use std::collections::VecDeque;
 
-fn main() -> DynResult<()> {
-    let mut prog = parse_intcode(&std::fs::read("day12-input.txt")?)?;
-
-    macro_rules! tas {
-        (@+$x:tt) => { TasInput::Right($x) };
-        (@-$x:tt) => { TasInput::Left($x) };
-        (@!$x:tt) => { TasInput::Wait($x) };
-        ($($dir:tt $item:tt),*,) => { &[$(tas![@ $dir $item]),*] };
-    }
+use day12::arcade::{Action, Arcade, Chain, Controller, Listener};
+use day12::autopilot::Autopilot;
+use day12::export::FrameCapture;
+use day12::game::{Event, Game};
+use day12::movie::{Movie, Playback, Recorder};
+use day12::tui::TerminalUi;
+use day12::Day12;
 
-    let mut game = Game::new(tas![
-        +1, !9, -9, !10, +13, !20,
-        -2, !416, -20, !350, +12, !120,
-        -12, !313, +32, -31, !10, -1,
-        !10, +3, !5, +8, !20, +3,
-        !79, -14, +34, !294, -24, !30,
-        -7, !217, +26, !70, -26, !45,
-        -1, !10, +29, !3, -6, !58,
-        -8, +1, !180, -3, !20, +3,
-        !50, -3, !10, -1, !110, +5,
-        !35, -6, !30, +8, !30, +1,
-        !30, -11, !80, -1, !35, +13,
-        !2, -14, !80, +17, !66,
-    ]);
+const REWIND_FRAMES: usize = 1000;
+const TOOL_FLAGS: &[&str] = &[
+    "--memdiff",
+    "--play",
+    "--movie",
+    "--record",
+    "--events",
+    "--export",
+];
 
-    Machine::new(prog.clone()).execute(&mut game);
-    println!("Total of blocks: {}", game.count_tile(Tile::Block));
-    prog[0] = 2;
-    Machine::new(prog).execute(&mut game);
-    println!("Score at end: {}", game.score);
-    Ok(())
-}
-#[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Tile {
-    Empty = 0,
-    Wall = 1,
-    Block = 2,
-    HorizontalPaddle = 3,
-    Ball = 4,
-}
+// Prints the events of the game as they happen, for `--events`.
+struct EventLog;
 
-impl Tile {
-    fn glyph(self) -> &'static str {
-        match self {
-            Tile::Empty => " ",
-            Tile::Wall => "#",
-            Tile::Block => "█",
-            Tile::HorizontalPaddle => "-",
-            Tile::Ball => "o",
+impl Listener for EventLog {
+    fn on_event(&mut self, event: &Event, _game: &Game) {
+        match event {
+            Event::TileChanged { x, y, old, new } => {
+                println!("tile {},{}: {:?} -> {:?}", x, y, old, new)
+            }
+            Event::ScoreChanged { old, new } => println!("score: {} -> {}", old, new),
+            Event::FrameCompleted { frame } => println!("frame {} done", frame),
+            Event::Rewound { frame } => println!("rewound to frame {}", frame),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum TasInput {
-    Left(usize),
-    Right(usize),
-    Wait(usize),
+fn arg_value(args: &[String], name: &str) -> Option<String> {
+    args.iter().skip_while(|arg| *arg != name).nth(1).cloned()
 }
 
-impl TasInput {
-    fn step(self) -> (Option<Self>, Word) {
-        match self {
-            Self::Left(x) if x <= 1 => (None, -1),
-            Self::Right(x) if x <= 1 => (None, 1),
-            Self::Wait(x) if x <= 1 => (None, 0),
-            Self::Left(x) => (Some(TasInput::Left(x - 1)), -1),
-            Self::Right(x) => (Some(TasInput::Right(x - 1)), 1),
-            Self::Wait(x) => (Some(TasInput::Wait(x - 1)), 0),
-        }
+// Prints the answers, unless asked to play the game in some other way.
+fn main() -> DynResult<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|arg| TOOL_FLAGS.contains(&arg.as_str())) {
+        return aoc_runner::main::<Day12>();
     }
-}
+    let input = Puzzle::of::<Day12>().input(Args::parse(&args)?.input.as_deref())?;
+    let mut prog = Day12::parse(&input)?;
 
-struct Game {
-    board: [[Tile; 64]; 64],
-    board_max: (usize, usize),
-    score: usize,
-    io_buffer: VecDeque<Word>,
-    tas_input: Vec<TasInput>,
-}
+    let memdiff_frame = match arg_value(&args, "--memdiff") {
+        Some(frame) => Some(frame.parse::<usize>()?),
+        None => None,
+    };
+    let play = args.iter().any(|arg| arg == "--play");
+    let mut playback = match arg_value(&args, "--movie") {
+        Some(path) => Some(Playback::new(&Movie::load(path)?)),
+        None => None,
+    };
+    let record = arg_value(&args, "--record");
+    let events = args.iter().any(|arg| arg == "--events");
+    let export = arg_value(&args, "--export");
 
-impl Io for Game {
-    fn read_in(&mut self) -> Option<Word> {
-        if let Some(tas_input) = self.tas_input.pop() {
-            let (steps_left, step) = tas_input.step();
-            self.tas_input.extend(steps_left);
-            Some(step)
-        } else {
-            self.draw();
-            StdIo.read_in()
-        }
+    prog[0] = 2;
+    let rewind = if play { REWIND_FRAMES } else { 0 };
+    let mut arcade = Arcade::with_rewind(prog.clone(), rewind);
+    if events && !play {
+        arcade.subscribe(EventLog);
+    }
+    let capture = export
+        .as_ref()
+        .map(|_| arcade.subscribe(FrameCapture::new()));
+    if let Some(frame) = memdiff_frame {
+        let controller: &mut dyn Controller = match &mut playback {
+            Some(playback) => playback,
+            None => &mut Autopilot,
+        };
+        print_frame_diff(&mut arcade, controller, frame, prog.len())?;
+        return Ok(());
     }
 
-    fn write_out(&mut self, data: Word) -> bool {
-        self.io_buffer.push_back(data);
-        while self.io_buffer.len() >= 3 {
-            let a = self.io_buffer.pop_front().unwrap();
-            let b = self.io_buffer.pop_front().unwrap();
-            let c = self.io_buffer.pop_front().unwrap();
-
-            if a == -1 && b == 0 {
-                self.score = c as usize;
-            } else {
-                let x = a as usize;
-                let y = b as usize;
-                let tile = match c {
-                    0 => Tile::Empty,
-                    1 => Tile::Wall,
-                    2 => Tile::Block,
-                    3 => Tile::HorizontalPaddle,
-                    4 => Tile::Ball,
-                    t => panic!("Unknown tile {}", t),
-                };
+    // A movie is played first, `--play` continues interactively from where it
+    // ends. Without either the autopilot plays.
+    let mut autopilot = Autopilot;
+    let mut tui = if play { Some(TerminalUi::new()?) } else { None };
+    let mut controllers: Vec<&mut dyn Controller> = Vec::new();
+    if let Some(playback) = &mut playback {
+        controllers.push(playback);
+    }
+    if let Some(tui) = &mut tui {
+        controllers.push(tui);
+    }
+    if controllers.is_empty() {
+        controllers.push(&mut autopilot);
+    }
+    let mut chain = Chain::new(controllers);
+    let movie = match &record {
+        Some(_) => {
+            let mut recorder = Recorder::new(&mut chain);
+            arcade.run(&mut recorder)?;
+            Some(recorder.finish(&arcade.game))
+        }
+        None => {
+            arcade.run(&mut chain)?;
+            None
+        }
+    };
+    drop(chain);
+    drop(tui);
 
-                self.board[y][x] = tile;
-                self.board_max.0 = self.board_max.0.max(x);
-                self.board_max.1 = self.board_max.1.max(y);
-            }
+    if let Some(playback) = &playback {
+        println!(
+            "Movie: played {} of {} frames",
+            playback.played(),
+            playback.frames()
+        );
+        playback.finish(&arcade.game)?;
+    }
+    if let (Some(path), Some(movie)) = (record, movie) {
+        movie.save(&path)?;
+        println!("Recorded {} frames to {}", movie.frames(), path);
+    }
+    if let (Some(path), Some(capture)) = (export, capture) {
+        // the last frame has no joystick read to capture it
+        let mut capture = capture.borrow_mut();
+        capture.capture(&arcade.game);
+        if path.ends_with(".gif") {
+            capture.save_gif(&path)?;
+        } else {
+            capture.save_png_sequence(&path)?;
         }
-        true
+        println!("Exported {} frames to {}", capture.frames(), path);
     }
+    println!("Score at end: {}", arcade.game.score);
+    Ok(())
 }
 
-impl Game {
-    fn new(input: &[TasInput]) -> Self {
-        Self {
-            board: [[Tile::Empty; 64]; 64],
-            board_max: (0, 0),
-            score: 0,
-            io_buffer: VecDeque::new(),
-            tas_input: input.into_iter().rev().cloned().collect(),
+// Shows what the program changed in memory while processing the joystick
+// input of `frame`, frame 0 being the initial drawing. The stack is what the
+// relative base went through during that frame.
+fn print_frame_diff(
+    arcade: &mut Arcade,
+    controller: &mut dyn Controller,
+    frame: usize,
+    code_len: usize,
+) -> DynResult<()> {
+    let mut joystick = |arcade: &Arcade| match controller.action(&arcade.game)? {
+        Action::Move(joystick) if !arcade.halted() => Ok(Some(joystick)),
+        _ => {
+            println!("Game ended before frame {}", frame);
+            DynResult::Ok(None)
         }
-    }
-    fn draw(&self) {
-        for y in 0..self.board_max.1 {
-            for x in 0..self.board_max.0 {
-                let tile = &self.board[y][x];
-                print!("{}", tile.glyph());
+    };
+    if frame > 0 {
+        arcade.frame(None)?;
+        while arcade.game.frames + 1 < frame {
+            match joystick(arcade)? {
+                Some(joystick) => arcade.frame(Some(joystick))?,
+                None => return Ok(()),
             }
-            print!("\n");
         }
-        println!("Score: {}", self.score);
+        match joystick(arcade)? {
+            Some(joystick) => arcade.game.set_joystick(joystick),
+            None => return Ok(()),
+        }
     }
 
-    fn count_tile(&self, tile: Tile) -> usize {
-        self.board
-            .iter()
-            .map(|row| row.iter().filter(|t| **t == tile).count())
-            .sum()
+    let before = arcade.machine.clone();
+    let mut stack = StackExtent::new(&before);
+    let after = snapshot_at(&mut arcade.machine, &mut arcade.game, u64::MAX, &mut stack)?;
+    if let Some(error) = arcade.game.take_error() {
+        return Err(format!("{}. IP: {}", error, after.ip()).into());
     }
+    let diff = MemDiff::between(&before, &after);
+    print!("{}", diff);
+    let view = MemoryView::new(after.memory())
+        .code(0..code_len as Word)
+        .stack(stack.range())
+        .written(&diff)
+        .color(true);
+    print!("{}", view);
+    Ok(())
 }
@@ -0,0 +1,303 @@
+use intcode::*;
+use std::fmt;
+use std::path::Path;
+
+use crate::arcade::{Action, Controller};
+use crate::game::{Game, Tile};
+
+// Text format, one entry per line:
+//
+//   L 9 / R 9 / W 9        joystick left, right or neutral for 9 frames
+//   check 120 score 500    the score when frame 120 is about to be played
+//   check 120 blocks 3     same for the number of blocks left
+//
+// `#` starts a comment. Frame N is the one playing the N-th joystick input,
+// frame 0 being the state before the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Score(usize),
+    Blocks(usize),
+}
+
+impl Check {
+    fn actual(self, game: &Game) -> Check {
+        match self {
+            Check::Score(_) => Check::Score(game.score),
+            Check::Blocks(_) => Check::Blocks(game.count_tile(Tile::Block)),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::Score(score) => write!(f, "score {}", score),
+            Check::Blocks(blocks) => write!(f, "blocks {}", blocks),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Movie {
+    runs: Vec<(Word, usize)>,
+    checks: Vec<(usize, Check)>,
+}
+
+fn direction(joystick: Word) -> char {
+    match joystick {
+        -1 => 'L',
+        0 => 'W',
+        1 => 'R',
+        _ => unreachable!("joystick {} is rejected by push", joystick),
+    }
+}
+
+impl Movie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> DynResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read movie {}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> DynResult<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    pub fn parse(text: &str) -> DynResult<Self> {
+        let mut movie = Self::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let error = || format!("Invalid movie line {}: {}", idx + 1, line.trim());
+            match words[..] {
+                [] => {}
+                [dir, count] => {
+                    let joystick = match dir {
+                        "L" => -1,
+                        "R" => 1,
+                        "W" => 0,
+                        _ => return Err(error().into()),
+                    };
+                    movie.push_run(joystick, count.parse().map_err(|_| error())?);
+                }
+                ["check", frame, kind, value] => {
+                    let frame = frame.parse().map_err(|_| error())?;
+                    let value = value.parse().map_err(|_| error())?;
+                    let check = match kind {
+                        "score" => Check::Score(value),
+                        "blocks" => Check::Blocks(value),
+                        _ => return Err(error().into()),
+                    };
+                    movie.add_check(frame, check);
+                }
+                _ => return Err(error().into()),
+            }
+        }
+        Ok(movie)
+    }
+
+    // Only left, neutral and right can be written down and played back.
+    pub fn push(&mut self, joystick: Word) -> DynResult<()> {
+        if !(-1..=1).contains(&joystick) {
+            return Err(format!("Cannot record joystick position {}", joystick).into());
+        }
+        self.push_run(joystick, 1);
+        Ok(())
+    }
+
+    fn push_run(&mut self, joystick: Word, count: usize) {
+        match self.runs.last_mut() {
+            Some((last, run)) if *last == joystick => *run += count,
+            _ if count > 0 => self.runs.push((joystick, count)),
+            _ => {}
+        }
+    }
+
+    pub fn add_check(&mut self, frame: usize, check: Check) {
+        self.checks.push((frame, check));
+        self.checks.sort_by_key(|(frame, _)| *frame);
+    }
+
+    pub fn frames(&self) -> usize {
+        self.runs.iter().map(|(_, count)| count).sum()
+    }
+
+    // Keeps the first `frames` inputs and the checks before them.
+    pub fn truncate(&mut self, frames: usize) {
+        let mut left = frames;
+        self.runs.retain_mut(|(_, count)| {
+            *count = (*count).min(left);
+            left -= *count;
+            *count > 0
+        });
+        self.checks.retain(|(at, _)| *at < frames);
+    }
+
+    pub fn checks(&self) -> &[(usize, Check)] {
+        &self.checks
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = Word> + '_ {
+        self.runs
+            .iter()
+            .flat_map(|(joystick, count)| std::iter::repeat_n(*joystick, *count))
+    }
+}
+
+impl fmt::Display for Movie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# arcade movie, {} frames", self.frames())?;
+        let mut checks = self.checks.iter().peekable();
+        let mut frame = 0;
+        for (joystick, count) in &self.runs {
+            while let Some((at, check)) = checks.next_if(|(at, _)| *at <= frame) {
+                writeln!(f, "check {} {}", at, check)?;
+            }
+            writeln!(f, "{} {}", direction(*joystick), count)?;
+            frame += count;
+        }
+        for (at, check) in checks {
+            writeln!(f, "check {} {}", at, check)?;
+        }
+        Ok(())
+    }
+}
+
+// Replays a movie, failing on the first checkpoint the game does not match.
+pub struct Playback {
+    inputs: Vec<Word>,
+    checks: Vec<(usize, Check)>,
+    played: usize,
+}
+
+impl Playback {
+    pub fn new(movie: &Movie) -> Self {
+        Self {
+            inputs: movie.inputs().collect(),
+            checks: movie.checks().to_vec(),
+            played: 0,
+        }
+    }
+
+    pub fn played(&self) -> usize {
+        self.played
+    }
+
+    pub fn frames(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn verify(&self, game: &Game) -> DynResult<()> {
+        for (_, expected) in self.checks.iter().filter(|(at, _)| *at == game.frames) {
+            let actual = expected.actual(game);
+            if actual != *expected {
+                return Err(format!(
+                    "Desync at frame {}: expected {}, got {}",
+                    game.frames, expected, actual
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    // Checks the final state once the game stopped, checkpoints past the end
+    // of the game are a desync as well.
+    pub fn finish(&self, game: &Game) -> DynResult<()> {
+        self.verify(game)?;
+        match self.checks.iter().find(|(at, _)| *at > game.frames) {
+            Some((at, check)) => Err(format!(
+                "Desync: game stopped at frame {}, before checking {} at frame {}",
+                game.frames, check, at
+            )
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Controller for Playback {
+    fn action(&mut self, game: &Game) -> DynResult<Action> {
+        self.verify(game)?;
+        match self.inputs.get(self.played) {
+            Some(joystick) => {
+                self.played += 1;
+                Ok(Action::Move(*joystick))
+            }
+            None => Ok(Action::Stop),
+        }
+    }
+}
+
+// Records the inputs of another controller, with a score checkpoint every
+// `CHECK_INTERVAL` frames. After a rewind the recording continues from the
+// frame the game went back to.
+pub struct Recorder<'a> {
+    inner: &'a mut dyn Controller,
+    movie: Movie,
+}
+
+const CHECK_INTERVAL: usize = 500;
+
+impl<'a> Recorder<'a> {
+    pub fn new(inner: &'a mut dyn Controller) -> Self {
+        Self {
+            inner,
+            movie: Movie::new(),
+        }
+    }
+
+    // Adds the final checkpoints and hands out the movie.
+    pub fn finish(mut self, game: &Game) -> Movie {
+        self.movie.add_check(game.frames, Check::Score(game.score));
+        self.movie
+            .add_check(game.frames, Check::Blocks(game.count_tile(Tile::Block)));
+        self.movie
+    }
+}
+
+impl Controller for Recorder<'_> {
+    fn action(&mut self, game: &Game) -> DynResult<Action> {
+        self.movie.truncate(game.frames);
+        if game.frames > 0 && game.frames % CHECK_INTERVAL == 0 {
+            self.movie.add_check(game.frames, Check::Score(game.score));
+        }
+        let action = self.inner.action(game)?;
+        if let Action::Move(joystick) = action {
+            self.movie.push(joystick)?;
+        }
+        Ok(action)
+    }
+
+    fn game_over(&mut self, game: &Game) -> DynResult<Action> {
+        self.inner.game_over(game)
+    }
+}
+
+#[test]
+fn test_movie_roundtrip() {
+    let text = "# comment\nR 1\nW 9\nW 1 # merged\ncheck 11 score 5\nL 2\n";
+    let movie = Movie::parse(text).unwrap();
+    assert_eq!(movie.frames(), 13);
+    assert_eq!(
+        movie.inputs().collect::<Vec<_>>(),
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -1, -1]
+    );
+    assert_eq!(
+        movie.to_string(),
+        "# arcade movie, 13 frames\nR 1\nW 10\ncheck 11 score 5\nL 2\n"
+    );
+    assert_eq!(Movie::parse(&movie.to_string()).unwrap(), movie);
+    assert!(Movie::parse("X 3").is_err());
+    assert!(movie.clone().push(2).is_err());
+
+    let mut rewound = movie.clone();
+    rewound.truncate(5);
+    assert_eq!(rewound.inputs().collect::<Vec<_>>(), &[1, 0, 0, 0, 0]);
+    assert!(rewound.checks().is_empty());
+}
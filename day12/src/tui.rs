@@ -0,0 +1,186 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use intcode::*;
+use std::io::{stdout, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crate::arcade::{Action, Controller};
+use crate::game::{Game, Tile};
+
+const HELP: &str = "←/→ move  space pause  r rewind  +/- speed  q quit";
+const REWIND_TIME: Duration = Duration::from_secs(3);
+
+enum Key {
+    Move(Word),
+    Rewind,
+    Pause,
+    Faster,
+    Slower,
+    Quit,
+}
+
+fn key(event: KeyEvent) -> Option<Key> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+    match event.code {
+        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('h') => Some(Key::Move(-1)),
+        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('l') => Some(Key::Move(1)),
+        KeyCode::Char('r') | KeyCode::Backspace => Some(Key::Rewind),
+        KeyCode::Char(' ') | KeyCode::Char('p') => Some(Key::Pause),
+        KeyCode::Char('+') | KeyCode::Char('=') => Some(Key::Faster),
+        KeyCode::Char('-') => Some(Key::Slower),
+        KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => Some(Key::Quit),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Key::Quit),
+        _ => None,
+    }
+}
+
+// Real-time frontend, every frame lasts `frame_time` and the joystick follows
+// the last direction key pressed during it. Only tiles that changed since the
+// previous frame are redrawn. Rewinding goes back `REWIND_TIME` worth of
+// frames at the current speed.
+pub struct TerminalUi {
+    out: Stdout,
+    drawn: Vec<Vec<Tile>>,
+    frame_time: Duration,
+    paused: bool,
+    over: bool,
+}
+
+impl TerminalUi {
+    pub fn new() -> DynResult<Self> {
+        let mut out = stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(Self {
+            out,
+            drawn: Vec::new(),
+            frame_time: Duration::from_millis(80),
+            paused: false,
+            over: false,
+        })
+    }
+
+    fn draw(&mut self, game: &Game) -> DynResult<()> {
+        let (width, height) = game.size();
+        if self.drawn.len() != height || self.drawn.first().map(Vec::len) != Some(width) {
+            self.drawn = vec![vec![Tile::Empty; width]; height];
+            queue!(self.out, Clear(ClearType::All))?;
+        }
+        for (y, row) in self.drawn.iter_mut().enumerate() {
+            for (x, drawn) in row.iter_mut().enumerate() {
+                let tile = game.tile(x, y);
+                if *drawn != tile {
+                    *drawn = tile;
+                    queue!(self.out, MoveTo(x as u16, y as u16), Print(tile.glyph()))?;
+                }
+            }
+        }
+
+        let state = if self.over {
+            "  GAME OVER"
+        } else if self.paused {
+            "  PAUSED"
+        } else {
+            ""
+        };
+        let panel = [
+            format!("Score: {:<10}{}", game.score, state),
+            format!(
+                "Blocks left: {:<6}Frame: {:<8}Frame time: {}ms   ",
+                game.count_tile(Tile::Block),
+                game.frames,
+                self.frame_time.as_millis()
+            ),
+            HELP.to_string(),
+        ];
+        for (idx, line) in panel.iter().enumerate() {
+            queue!(
+                self.out,
+                MoveTo(0, (height + 1 + idx) as u16),
+                Clear(ClearType::CurrentLine),
+                Print(line)
+            )?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn rewind_frames(&self) -> usize {
+        (REWIND_TIME.as_millis() / self.frame_time.as_millis()).max(1) as usize
+    }
+
+    // Collects keys until the frame time passes, or for as long as it takes
+    // while paused or after the game is over.
+    fn input(&mut self, game: &Game, over: bool) -> DynResult<Action> {
+        self.over = over;
+        self.draw(game)?;
+        let deadline = Instant::now() + self.frame_time;
+        let mut joystick = 0;
+        loop {
+            let waiting = self.paused || self.over;
+            let timeout = if waiting {
+                Duration::from_secs(3600)
+            } else {
+                deadline.saturating_duration_since(Instant::now())
+            };
+            if !event::poll(timeout)? {
+                if waiting {
+                    continue;
+                }
+                return Ok(Action::Move(joystick));
+            }
+            let event = match event::read()? {
+                Event::Key(event) => event,
+                Event::Resize(..) => {
+                    self.drawn.clear();
+                    self.draw(game)?;
+                    continue;
+                }
+                _ => continue,
+            };
+            match key(event) {
+                Some(Key::Move(direction)) => joystick = direction,
+                Some(Key::Rewind) => return Ok(Action::Rewind(self.rewind_frames())),
+                Some(Key::Pause) if !self.over => {
+                    self.paused = !self.paused;
+                    self.draw(game)?;
+                    if !self.paused {
+                        return Ok(Action::Move(joystick));
+                    }
+                }
+                Some(Key::Faster) => {
+                    self.frame_time = (self.frame_time / 2).max(Duration::from_millis(5));
+                    self.draw(game)?;
+                }
+                Some(Key::Slower) => {
+                    self.frame_time = (self.frame_time * 2).min(Duration::from_secs(2));
+                    self.draw(game)?;
+                }
+                Some(Key::Quit) => return Ok(Action::Stop),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Controller for TerminalUi {
+    fn action(&mut self, game: &Game) -> DynResult<Action> {
+        self.input(game, false)
+    }
+
+    fn game_over(&mut self, game: &Game) -> DynResult<Action> {
+        self.input(game, true)
+    }
+}
+
+impl Drop for TerminalUi {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,49 @@
+use aoc_runner::{DynResult, Solution};
+use intcode::*;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    fn part1(mem: &Vec<Word>) -> DynResult<String> {
+        Ok(run(mem, 12, 2)?.to_string())
+    }
+
+    fn part2(mem: &Vec<Word>) -> DynResult<String> {
+        let (noun, verb) = noun_verb(mem)?;
+        Ok((100 * noun + verb).to_string())
+    }
+}
+
+// Value left at address 0 for the given noun and verb.
+pub fn run(mem: &[Word], noun: Word, verb: Word) -> DynResult<Word> {
+    let mut mem = mem.to_vec();
+    mem[1] = noun;
+    mem[2] = verb;
+    let mut machine = Machine::new(mem);
+    machine.run_until_blocked(&mut BufIo::new(Vec::new()))?;
+    Ok(machine.read_mem_at(0))
+}
+
+// Solves for the noun and verb producing 19690720 symbolically.
+pub fn noun_verb(mem: &[Word]) -> DynResult<(Word, Word)> {
+    let mut m = SymbolicMachine::new(mem.to_vec());
+    m.set_symbolic(1, "noun");
+    m.set_symbolic(2, "verb");
+    m.execute()?;
+
+    let output = m
+        .mem_at(0)
+        .linear()
+        .ok_or("Output is not linear in noun and verb")?;
+    let solution = output
+        .solve(19690720, &[("noun", 0..100), ("verb", 0..100)])
+        .ok_or("No noun and verb produce 19690720")?;
+    Ok((solution[0], solution[1]))
+}
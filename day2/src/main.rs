@@ -1,26 +1,20 @@
+use aoc_runner::{Args, DynResult, Puzzle, Solution};
+use day2::Day2;
 use intcode::*;
 
 fn main() -> DynResult<()> {
-    let file = std::fs::read("day2-input.txt")?;
-    let mem = parse_intcode(&file)?;
-
-    for noun in 0..100 {
-        for verb in 0..100 {
-            let mut mem = mem.clone();
-            mem[1] = noun;
-            mem[2] = verb;
-
-            let mut m = Machine::new(mem);
-            m.execute(&mut StdIo);
-
-            let output = m.read_mem_at(0);
-
-            if output == 19690720 {
-                println!("noun: {}, verb: {}", noun, verb);
-                return Ok(());
-            }
-        }
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|arg| arg == "--self-modifying") {
+        return aoc_runner::main::<Day2>();
     }
 
+    let input = Puzzle::of::<Day2>().input(Args::parse(&args)?.input.as_deref())?;
+    let mut mem = Day2::parse(&input)?;
+    let (noun, verb) = day2::noun_verb(&mem)?;
+    mem[1] = noun;
+    mem[2] = verb;
+    let mut detector = SelfModDetector::new();
+    Machine::new(mem).execute_observed(&mut StdIo, &mut detector);
+    print!("{}", detector.report());
     Ok(())
 }
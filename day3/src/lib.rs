@@ -0,0 +1,191 @@
+use aoc_runner::{DynResult, Solution};
+use grid::{Dir, Point};
+use std::collections::HashMap;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+    type Input = (SegmentedPath, SegmentedPath);
+
+    fn parse(input: &str) -> DynResult<Self::Input> {
+        let mut split = input.lines();
+        let path1 = SegmentedPath::parse(split.next().ok_or("Missing path 1")?.as_bytes());
+        let path2 = SegmentedPath::parse(split.next().ok_or("Missing path 2")?.as_bytes());
+        Ok((path1, path2))
+    }
+
+    fn part1((path1, path2): &Self::Input) -> DynResult<String> {
+        let lowest_manhattan = Board::from_path(path1)
+            .intersect(path2)
+            .map(|i| i.cursor.manhattan())
+            .min()
+            .ok_or("Paths do not intersect")?;
+        Ok(lowest_manhattan.to_string())
+    }
+
+    fn part2((path1, path2): &Self::Input) -> DynResult<String> {
+        let shortest_path = Board::from_path(path1)
+            .intersect(path2)
+            .map(|i| i.lhs_distance + i.rhs_distance)
+            .min()
+            .ok_or("Paths do not intersect")?;
+        Ok(shortest_path.to_string())
+    }
+}
+
+struct Board {
+    map: HashMap<Point, usize>,
+}
+
+impl Board {
+    fn from_path(path: &SegmentedPath) -> Self {
+        let mut map = HashMap::new();
+        for (len, cursor) in path.iter().enumerate() {
+            map.entry(cursor).or_insert(len + 1);
+        }
+        Self { map }
+    }
+
+    fn intersect<'a: 'c, 'b: 'c, 'c>(
+        &'a self,
+        path: &'b SegmentedPath,
+    ) -> impl Iterator<Item = Intersection> + 'c {
+        path.iter()
+            .enumerate()
+            .filter_map(move |(path_len, cursor)| {
+                let map_len = self.map.get(&cursor)?;
+                Some(Intersection {
+                    cursor,
+                    lhs_distance: *map_len,
+                    rhs_distance: path_len + 1,
+                })
+            })
+    }
+}
+
+struct Intersection {
+    cursor: Point,
+    lhs_distance: usize,
+    rhs_distance: usize,
+}
+
+pub struct SegmentedPath {
+    segments: Vec<Segment>,
+}
+
+struct PathIterator<'a> {
+    path: std::slice::Iter<'a, Segment>,
+    last_segment: Option<Segment>,
+    cursor: Point,
+}
+
+impl Iterator for PathIterator<'_> {
+    type Item = Point;
+    fn next(&mut self) -> Option<Point> {
+        loop {
+            if let Some(last_segment) = self.last_segment.as_mut() {
+                if last_segment.step_size() != 0 {
+                    self.cursor += last_segment.dir().offset();
+                    last_segment.mutate(|v| v - 1);
+                    return Some(self.cursor);
+                } else {
+                    self.last_segment = None;
+                }
+            }
+
+            if let Some(seg) = self.path.next() {
+                self.last_segment = Some(seg.clone());
+                continue;
+            }
+
+            return None;
+        }
+    }
+}
+
+impl SegmentedPath {
+    fn iter<'a>(&'a self) -> impl Iterator<Item = Point> + 'a {
+        PathIterator {
+            path: self.segments.iter(),
+            last_segment: None,
+            cursor: Point::ORIGIN,
+        }
+    }
+
+    fn parse(data: &[u8]) -> SegmentedPath {
+        let mut seg = None;
+        let mut vec = Vec::new();
+
+        for character in data {
+            let c = *character as char;
+            match c {
+                ',' | '\n' => {
+                    vec.extend(seg.take());
+                }
+                'R' | 'L' | 'U' | 'D' => {
+                    if seg.is_some() {
+                        panic!("Unexpected start of new segment: {}", c);
+                    }
+                    seg = Some(match c {
+                        'R' => Segment::R(0),
+                        'L' => Segment::L(0),
+                        'U' => Segment::U(0),
+                        'D' => Segment::D(0),
+                        _ => unreachable!(),
+                    });
+                }
+                '0'..='9' => {
+                    if let Some(segment) = seg.as_mut() {
+                        segment.mutate(|value| value * 10 + (c as usize - '0' as usize));
+                    } else {
+                        panic!("Unexpected characted for segment value: {}", c);
+                    }
+                }
+                c => panic!("Unexpected character: {}", c),
+            }
+        }
+
+        vec.extend(seg.take());
+        SegmentedPath { segments: vec }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    R(usize),
+    L(usize),
+    U(usize),
+    D(usize),
+}
+
+impl Segment {
+    #[inline]
+    fn mutate(&mut self, f: impl Fn(usize) -> usize) {
+        match self {
+            Segment::R(v) => *v = f(*v),
+            Segment::L(v) => *v = f(*v),
+            Segment::U(v) => *v = f(*v),
+            Segment::D(v) => *v = f(*v),
+        }
+    }
+
+    fn dir(&self) -> Dir {
+        match self {
+            Segment::R(_) => Dir::Right,
+            Segment::L(_) => Dir::Left,
+            Segment::U(_) => Dir::Up,
+            Segment::D(_) => Dir::Down,
+        }
+    }
+
+    #[inline]
+    fn step_size(&self) -> usize {
+        match self {
+            Segment::R(v) => *v,
+            Segment::L(v) => *v,
+            Segment::U(v) => *v,
+            Segment::D(v) => *v,
+        }
+    }
+}
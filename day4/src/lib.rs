@@ -0,0 +1,50 @@
+use aoc_runner::{DynResult, Solution};
+use std::ops::Range;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+    const INPUT: Option<&'static str> = Some("153517-630395");
+    type Input = Range<u32>;
+
+    fn parse(input: &str) -> DynResult<Range<u32>> {
+        let (low, high) = input
+            .trim()
+            .split_once('-')
+            .ok_or("Expected a range like 100-200")?;
+        Ok(low.parse()?..high.parse::<u32>()? + 1)
+    }
+
+    fn part1(range: &Range<u32>) -> DynResult<String> {
+        Ok(count(range, |counts| counts.iter().any(|count| *count >= 2)).to_string())
+    }
+
+    fn part2(range: &Range<u32>) -> DynResult<String> {
+        Ok(count(range, |counts| counts.contains(&2)).to_string())
+    }
+}
+
+// Passwords with never decreasing digits whose digit counts are accepted.
+fn count(range: &Range<u32>, accept: impl Fn(&[usize; 10]) -> bool) -> usize {
+    let mut total = 0;
+    'search: for input in range.clone() {
+        let mut remaining = input as usize;
+        let mut counts = [0usize; 10];
+        let mut last = None;
+        while remaining > 0 {
+            let digit = remaining % 10;
+            if last.is_some_and(|last| digit > last) {
+                continue 'search;
+            }
+            last.replace(digit);
+            remaining /= 10;
+            counts[digit] += 1;
+        }
+
+        if accept(&counts) {
+            total += 1;
+        }
+    }
+    total
+}
@@ -0,0 +1,34 @@
+use aoc_runner::{DynResult, Solution};
+use intcode::*;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    fn part1(mem: &Vec<Word>) -> DynResult<String> {
+        diagnostic_code(mem, 1)
+    }
+
+    fn part2(mem: &Vec<Word>) -> DynResult<String> {
+        diagnostic_code(mem, 5)
+    }
+}
+
+// The last output of the diagnostic program, the ones before it are test
+// results that have to be 0.
+fn diagnostic_code(mem: &[Word], system_id: Word) -> DynResult<String> {
+    let mut io = BufIo::new(vec![system_id]);
+    Machine::new(mem.to_vec()).run_until_blocked(&mut io)?;
+    let output = io.into_output();
+    let (code, tests) = output.split_last().ok_or("No diagnostic output")?;
+    if let Some(failed) = tests.iter().position(|test| *test != 0) {
+        return Err(format!("Diagnostic test {} failed: {:?}", failed, tests).into());
+    }
+    Ok(code.to_string())
+}
@@ -1,10 +1,47 @@
+use aoc_runner::{Args, DynResult, Puzzle, Solution};
+use day5::Day5;
 use intcode::*;
 
+// Without arguments prints the answers, otherwise the coverage of running the
+// given system ids. With only `--annotate` the ids are read from stdin, like
+// the program always did before.
 fn main() -> DynResult<()> {
-    let file = std::fs::read("day5-input.txt")?;
-    let mem = parse_intcode(&file)?;
+    let all_args: Vec<String> = std::env::args().collect();
+    let mut annotate = false;
+    let mut system_ids = Vec::new();
+    let mut args = all_args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--annotate" => annotate = true,
+            // taken by the runner
+            "--part" | "--input" => {
+                args.next();
+            }
+            id => system_ids.push(id.parse::<Word>()?),
+        }
+    }
+    if !annotate && system_ids.is_empty() {
+        return aoc_runner::main::<Day5>();
+    }
 
-    let mut m = Machine::new(mem);
-    m.execute(&mut StdIo);
+    let input = Puzzle::of::<Day5>().input(Args::parse(&all_args)?.input.as_deref())?;
+    let mem = Day5::parse(&input)?;
+
+    let mut coverage = Coverage::new();
+    if system_ids.is_empty() {
+        Machine::new(mem.clone()).execute_observed(&mut StdIo, &mut coverage);
+    }
+    for id in system_ids {
+        let mut io = BufIo::new(vec![id]);
+        Machine::new(mem.clone()).execute_observed(&mut io, &mut coverage);
+        println!("System {}: {:?}", id, io.into_output());
+    }
+
+    let isa = InstructionSet::new();
+    if annotate {
+        print!("{}", coverage.annotate(&mem, &isa));
+    } else {
+        println!("Coverage: {}", coverage.summary(&mem, &isa));
+    }
     Ok(())
 }
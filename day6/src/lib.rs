@@ -0,0 +1,133 @@
+use std::collections::{HashMap, VecDeque};
+
+use aoc_runner::{DynResult, Solution};
+
+pub struct Day6;
+
+// Bodies orbiting each body, and the body each one orbits.
+pub struct Orbits {
+    orbiters_map: HashMap<u64, Vec<u64>>,
+    orbits_map: HashMap<u64, u64>,
+}
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+    type Input = Orbits;
+
+    fn parse(input: &str) -> DynResult<Orbits> {
+        parse_orbits(input.as_bytes())
+    }
+
+    fn part1(orbits: &Orbits) -> DynResult<String> {
+        Ok(total_orbit_relations(orbits).to_string())
+    }
+
+    fn part2(orbits: &Orbits) -> DynResult<String> {
+        let distance = transfers(orbits).ok_or("YOU and SAN do not orbit a common body")?;
+        Ok(distance.to_string())
+    }
+}
+
+fn parse_orbits(file: &[u8]) -> DynResult<Orbits> {
+    let data = file
+        .split(|c| *c as char == '\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty());
+
+    let mut orbiters_map = HashMap::<u64, Vec<u64>>::new();
+    let mut orbits_map = HashMap::<u64, u64>::new();
+
+    for orbit_data in data {
+        let mut split = orbit_data.split(|c| *c as char == ')');
+        let lhs = split.next().unwrap_or_else(|| {
+            panic!(
+                "Missing orbit center in '{}'",
+                std::str::from_utf8(orbit_data).unwrap()
+            )
+        });
+        let rhs = split.next().unwrap_or_else(|| {
+            panic!(
+                "Missing orbiting body in '{}'",
+                std::str::from_utf8(orbit_data).unwrap()
+            )
+        });
+        assert_eq!(split.next(), None);
+        let lhs = as_num(lhs);
+        let rhs = as_num(rhs);
+        orbiters_map
+            .entry(lhs)
+            .and_modify(|vec| vec.push(rhs))
+            .or_insert_with(|| vec![rhs]);
+        orbits_map.insert(rhs, lhs);
+    }
+    Ok(Orbits {
+        orbiters_map,
+        orbits_map,
+    })
+}
+
+fn total_orbit_relations(orbits: &Orbits) -> u64 {
+    let Orbits {
+        orbiters_map,
+        orbits_map,
+    } = orbits;
+    let mut orbits_count_map = HashMap::<u64, u64>::new();
+    let mut queue = VecDeque::new();
+
+    for body in orbiters_map.keys() {
+        // start processing from root orbited entries
+        if !orbits_map.contains_key(body) {
+            queue.push_back(body);
+        }
+    }
+
+    while let Some(body) = queue.pop_front() {
+        if let Some(orbited_body) = orbits_map.get(body) {
+            orbits_count_map.insert(*body, 1 + *orbits_count_map.get(orbited_body).unwrap());
+        } else {
+            orbits_count_map.insert(*body, 0);
+        }
+        if let Some(orbiters) = orbiters_map.get(body) {
+            queue.extend(orbiters);
+        }
+    }
+
+    orbits_count_map.values().sum()
+}
+
+// Orbital transfers needed to get from the body YOU orbit to the one SAN does.
+fn transfers(orbits: &Orbits) -> Option<u64> {
+    let orbits_map = &orbits.orbits_map;
+    let you = as_num("YOU".as_bytes());
+    let san = as_num("SAN".as_bytes());
+
+    let mut parent_dist_map = HashMap::new();
+    let mut current = you;
+    let mut path_len = 0;
+    parent_dist_map.insert(you, 0);
+    while let Some(parent) = orbits_map.get(&current) {
+        path_len += 1;
+        current = *parent;
+        parent_dist_map.insert(*parent, path_len);
+    }
+
+    let mut current = san;
+    let mut path_len = 0;
+    while let Some(parent) = orbits_map.get(&current) {
+        path_len += 1;
+        if let Some(path_to_you) = parent_dist_map.get(parent) {
+            return Some(path_to_you + path_len - 2);
+        }
+        current = *parent;
+    }
+    None
+}
+
+fn as_num(slice: &[u8]) -> u64 {
+    assert!(slice.len() <= 8);
+    let mut num = 0;
+    for i in 0..8 {
+        num |= (*slice.get(i).unwrap_or(&0) as u64) << (i * 8);
+    }
+    num
+}
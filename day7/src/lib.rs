@@ -0,0 +1,85 @@
+use aoc_runner::{DynResult, Solution};
+use intcode::*;
+use permutohedron::LexicalPermutation;
+use std::collections::VecDeque;
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    fn part1(amp_code: &Vec<Word>) -> DynResult<String> {
+        Ok(max_thrust(amp_code, [0, 1, 2, 3, 4], false).to_string())
+    }
+
+    fn part2(amp_code: &Vec<Word>) -> DynResult<String> {
+        Ok(max_thrust(amp_code, [5, 6, 7, 8, 9], true).to_string())
+    }
+}
+
+// Highest signal of all the phase permutations.
+fn max_thrust(amp_code: &[Word], mut phases: [Word; 5], feedback: bool) -> Word {
+    let mut max_output = Word::MIN;
+    loop {
+        let output = run_thruster_amps(amp_code, phases, feedback);
+        max_output = max_output.max(output.into_iter().last().unwrap());
+        if !phases.next_permutation() {
+            return max_output;
+        }
+    }
+}
+
+pub fn run_thruster_amps(code: &[Word], phases: [Word; 5], feedback: bool) -> VecDeque<Word> {
+    let mut amp_a = Machine::new(code.to_vec());
+    let mut amp_b = Machine::new(code.to_vec());
+    let mut amp_c = Machine::new(code.to_vec());
+    let mut amp_d = Machine::new(code.to_vec());
+    let mut amp_e = Machine::new(code.to_vec());
+
+    let mut buf0 = IoBuffer::with_data(&[phases[0], 0]);
+    let mut buf1 = IoBuffer::with_data(&[phases[1]]);
+    let mut buf2 = IoBuffer::with_data(&[phases[2]]);
+    let mut buf3 = IoBuffer::with_data(&[phases[3]]);
+    let mut buf4 = IoBuffer::with_data(&[phases[4]]);
+    let mut buf5 = IoBuffer::new();
+
+    loop {
+        let res_a = amp_a.step(&mut PipedIo::new(&mut buf0, &mut buf1));
+        let res_b = amp_b.step(&mut PipedIo::new(&mut buf1, &mut buf2));
+        let res_c = amp_c.step(&mut PipedIo::new(&mut buf2, &mut buf3));
+        let res_d = amp_d.step(&mut PipedIo::new(&mut buf3, &mut buf4));
+        let res_e_out = if feedback { &mut buf0 } else { &mut buf5 };
+        let res_e = amp_e.step(&mut PipedIo::new(&mut buf4, res_e_out));
+
+        match res_a.join(res_b).join(res_c).join(res_d).join(res_e) {
+            StepResult::Continue => continue,
+            StepResult::Halt => break,
+            StepResult::IoBlocked => panic!("All amplifiers blocked on IO"),
+        }
+    }
+
+    if feedback {
+        buf0.into_inner()
+    } else {
+        buf5.into_inner()
+    }
+}
+
+// the other amplifier examples live in intcode/tests/conformance
+#[test]
+fn test_thrusters_feedback() {
+    let output = run_thruster_amps(
+        &[
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ],
+        [9, 8, 7, 6, 5],
+        true,
+    );
+    assert_eq!(output, &[139629729]);
+}
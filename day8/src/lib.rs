@@ -0,0 +1,54 @@
+use aoc_runner::{DynResult, Solution};
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+    type Input = Vec<u8>;
+
+    fn parse(input: &str) -> DynResult<Vec<u8>> {
+        let data: Vec<u8> = input.trim().bytes().collect();
+        if data.is_empty() || data.len() % (WIDTH * HEIGHT) != 0 {
+            return Err(format!("{} digits do not make up whole layers", data.len()).into());
+        }
+        Ok(data)
+    }
+
+    fn part1(data: &Vec<u8>) -> DynResult<String> {
+        let mut min_layer = None;
+        for layer in data.chunks(WIDTH * HEIGHT) {
+            let count = |digit| layer.iter().filter(|d| **d == digit).count();
+            let zero_digits = count(b'0');
+            let replace = min_layer.is_none_or(|(zero, _)| zero > zero_digits);
+            if replace {
+                min_layer = Some((zero_digits, count(b'1') * count(b'2')));
+            }
+        }
+        Ok(min_layer.map_or(0, |(_, checksum)| checksum).to_string())
+    }
+
+    fn part2(data: &Vec<u8>) -> DynResult<String> {
+        Ok(ocr::recognize(&decode(data)))
+    }
+}
+
+// Pixels of the stacked layers, the first non transparent one wins.
+pub fn decode(data: &[u8]) -> Vec<Vec<bool>> {
+    let mut image = [2u8; WIDTH * HEIGHT];
+    for layer in data.chunks(WIDTH * HEIGHT) {
+        for (idx, digit) in layer.iter().enumerate() {
+            match *digit as char {
+                '0' if image[idx] == 2 => image[idx] = 0,
+                '1' if image[idx] == 2 => image[idx] = 1,
+                _ => {}
+            }
+        }
+    }
+    image
+        .chunks(WIDTH)
+        .map(|row| row.iter().map(|pixel| *pixel == 1).collect())
+        .collect()
+}
@@ -0,0 +1,35 @@
+use aoc_runner::{DynResult, Solution};
+use intcode::*;
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+    type Input = Vec<Word>;
+
+    fn parse(input: &str) -> DynResult<Vec<Word>> {
+        parse_intcode(input.as_bytes())
+    }
+
+    // BOOST in test mode, it outputs the opcodes it found broken before the
+    // keycode.
+    fn part1(boost: &Vec<Word>) -> DynResult<String> {
+        let output = run_boost(boost, 1)?;
+        match output[..] {
+            [keycode] => Ok(keycode.to_string()),
+            _ => Err(format!("BOOST reports malfunctioning opcodes: {:?}", output).into()),
+        }
+    }
+
+    fn part2(boost: &Vec<Word>) -> DynResult<String> {
+        let output = run_boost(boost, 2)?;
+        let coordinates = output.last().ok_or("BOOST produced no output")?;
+        Ok(coordinates.to_string())
+    }
+}
+
+fn run_boost(boost: &[Word], mode: Word) -> DynResult<Vec<Word>> {
+    let mut io = BufIo::new(vec![mode]);
+    Machine::new(boost.to_vec()).run_until_blocked(&mut io)?;
+    Ok(io.into_output())
+}
@@ -1,10 +1,3 @@
-use intcode::*;
-
-fn main() -> DynResult<()> {
-    let boost = parse_intcode(&std::fs::read("day9-input.txt")?)?;
-
-    let mut machine = Machine::new(boost);
-    machine.execute(&mut StdIo);
-
-    Ok(())
+fn main() -> aoc_runner::DynResult<()> {
+    aoc_runner::main::<day9::Day9>()
 }
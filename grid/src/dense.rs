@@ -0,0 +1,221 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::point::{Bounds, Point};
+
+// Bounded grid stored row by row, with the top left corner at the origin.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// Empty, without allocating.
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // Makes room for `point`, new cells get `fill`. Negative coordinates
+    // cannot be reached and are refused.
+    pub fn grow_to(&mut self, point: Point, fill: T) -> bool {
+        if point.x < 0 || point.y < 0 {
+            return false;
+        }
+        let width = self.width.max(point.x as usize + 1);
+        let height = self.height.max(point.y as usize + 1);
+        if (width, height) != (self.width, self.height) {
+            let mut cells = Vec::with_capacity(width * height);
+            for row in self.cells.chunks(self.width.max(1)).take(self.height) {
+                cells.extend_from_slice(row);
+                cells.resize(cells.len() + width - self.width, fill.clone());
+            }
+            cells.resize(width * height, fill);
+            *self = Self {
+                width,
+                height,
+                cells,
+            };
+        }
+        true
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len() % width == 0 || cells.is_empty(),
+            "{} cells do not fill rows of {}",
+            cells.len(),
+            width
+        );
+        Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        }
+    }
+
+    // One row per non-blank line. Characters mapped to None are skipped, all
+    // rows need to end up equally long.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, String> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().filter_map(&mut cell));
+            let len = cells.len() - before;
+            match width {
+                _ if len == 0 => {}
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(format!(
+                        "Line {} is {} cells wide instead of {}",
+                        idx + 1,
+                        len,
+                        width
+                    ))
+                }
+                _ => {}
+            }
+        }
+        Ok(Self::from_vec(width.unwrap_or(0), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let max = Point::new(self.width as isize - 1, self.height as isize - 1);
+        Some(Bounds::new(Point::ORIGIN, max))
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= 0
+            && point.y >= 0
+            && (point.x as usize) < self.width
+            && (point.y as usize) < self.height
+    }
+
+    fn offset(&self, point: Point) -> Option<usize> {
+        if self.contains(point) {
+            Some(point.y as usize * self.width + point.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.offset(point)
+            .map(move |offset| &mut self.cells[offset])
+    }
+
+    // Returns the previous value, None and no change when out of bounds.
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len())
+            .map(move |offset| Point::new((offset % width) as isize, (offset / width) as isize))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // The neighbors within the grid.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors4().filter(move |p| self.contains(*p))
+    }
+
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors8().filter(move |p| self.contains(*p))
+    }
+
+    // A line per row, each cell drawn by `glyph`.
+    pub fn render<'a, G: fmt::Display>(&'a self, glyph: impl Fn(&T) -> G + 'a) -> String {
+        let mut out = String::new();
+        for row in self.rows() {
+            for cell in row {
+                out.push_str(&glyph(cell).to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{:?} outside of {}x{} grid", point, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} outside of {}x{} grid", point, width, height))
+    }
+}
+
+#[test]
+fn test_parse_grow_render() {
+    let mut grid = Grid::parse("  #.\n\n  ..\n", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap();
+    assert_eq!((grid.width(), grid.height()), (2, 2));
+    assert!(grid[Point::new(0, 0)]);
+    assert_eq!(grid.get(Point::new(2, 0)), None);
+    assert_eq!(grid.neighbors4(Point::new(0, 0)).count(), 2);
+
+    assert!(grid.grow_to(Point::new(2, 2), true));
+    assert!(!grid.grow_to(Point::new(-1, 0), true));
+    assert_eq!(grid.set(Point::new(1, 1), true), Some(false));
+    let text = grid.render(|cell| if *cell { '#' } else { '.' });
+    assert_eq!(text, "#.#\n.##\n###\n");
+    assert!(Grid::parse("#\n##", Some).is_err());
+}
@@ -0,0 +1,7 @@
+mod dense;
+mod point;
+mod sparse;
+
+pub use dense::*;
+pub use point::*;
+pub use sparse::*;
@@ -0,0 +1,187 @@
+use std::borrow::Borrow;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+// Screen coordinates, y grows downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self) -> isize {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn step(self, dir: Dir) -> Self {
+        self + dir.offset()
+    }
+
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        Dir::ALL.iter().map(move |dir| self.step(*dir))
+    }
+
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point::new(x, y)))
+            .filter(|offset| *offset != Point::ORIGIN)
+            .map(move |offset| self + offset)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+    fn mul(self, factor: isize) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Right,
+    Left,
+}
+
+impl Dir {
+    // Clockwise, starting up.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Dir::Up => Dir::Left,
+            Dir::Down => Dir::Right,
+            Dir::Right => Dir::Up,
+            Dir::Left => Dir::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Dir::Up => Dir::Right,
+            Dir::Down => Dir::Left,
+            Dir::Right => Dir::Down,
+            Dir::Left => Dir::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_left().turn_left()
+    }
+
+    pub fn offset(self) -> Point {
+        match self {
+            Dir::Up => Point::new(0, -1),
+            Dir::Down => Point::new(0, 1),
+            Dir::Right => Point::new(1, 0),
+            Dir::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+// Inclusive bounding box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    // None without any points.
+    pub fn of<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Point>,
+    {
+        let mut points = points.into_iter().map(|p| *p.borrow());
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), Self::include))
+    }
+
+    pub fn include(self, point: Point) -> Self {
+        Self {
+            min: Point::new(self.min.x.min(point.x), self.min.y.min(point.y)),
+            max: Point::new(self.max.x.max(point.x), self.max.y.max(point.y)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    // Row by row, from the top left.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[test]
+fn test_turns_and_bounds() {
+    let mut dir = Dir::Up;
+    let mut pos = Point::ORIGIN;
+    for _ in 0..4 {
+        pos = pos.step(dir);
+        dir = dir.turn_right();
+    }
+    assert_eq!((pos, dir), (Point::ORIGIN, Dir::Up));
+    assert_eq!(Dir::Left.reverse(), Dir::Right);
+    assert_eq!(Point::new(2, 3).neighbors8().count(), 8);
+
+    let bounds = Bounds::of([Point::new(1, -2), Point::new(-3, 4)]).unwrap();
+    assert_eq!((bounds.width(), bounds.height()), (5, 7));
+    assert!(bounds.contains(Point::new(0, 0)));
+    assert_eq!(bounds.points().next(), Some(Point::new(-3, -2)));
+    assert_eq!(Bounds::of(Vec::<Point>::new()), None);
+}
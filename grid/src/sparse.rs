@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FromIterator;
+
+use crate::point::{Bounds, Point};
+
+// Unbounded grid only storing the cells that were set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    // Lines from the top, starting at the origin. Characters mapped to None
+    // leave their cell unset.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> Option<T>) -> Self {
+        let mut grid = Self::new();
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(value) = cell(c) {
+                    grid.insert(Point::new(x as isize, y as isize), value);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn entry(&mut self, point: Point) -> std::collections::hash_map::Entry<'_, Point, T> {
+        self.cells.entry(point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+
+    // Bounding box of the set cells.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::of(self.cells.keys())
+    }
+
+    // The neighbors that are set.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors4().filter(move |p| self.contains(*p))
+    }
+
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors8().filter(move |p| self.contains(*p))
+    }
+
+    // A line per row of `bounds`, each cell drawn by `glyph`.
+    pub fn render_within<G: fmt::Display>(
+        &self,
+        bounds: Bounds,
+        glyph: impl Fn(Point, Option<&T>) -> G,
+    ) -> String {
+        let mut out = String::new();
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                let point = Point::new(x, y);
+                out.push_str(&glyph(point, self.get(point)).to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // Renders the bounding box of the set cells.
+    pub fn render<G: fmt::Display>(&self, glyph: impl Fn(Option<&T>) -> G) -> String {
+        match self.bounds() {
+            Some(bounds) => self.render_within(bounds, |_, cell| glyph(cell)),
+            None => String::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[test]
+fn test_sparse() {
+    let mut grid = SparseGrid::parse(".#\n #", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    });
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid.get(Point::new(0, 1)), None);
+    grid.insert(Point::new(-1, 2), true);
+    assert_eq!(
+        grid.bounds(),
+        Some(Bounds::new(Point::new(-1, 0), Point::new(1, 2)))
+    );
+    assert_eq!(grid.neighbors8(Point::new(0, 1)).count(), 4);
+    let text = grid.render(|cell| match cell {
+        Some(true) => '#',
+        Some(false) => '.',
+        None => ' ',
+    });
+    assert_eq!(text, " .#\n  #\n#  \n");
+}
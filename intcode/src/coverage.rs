@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+
+use crate::disasm::{disassemble_isa, Line};
+use crate::extension::InstructionSet;
+use crate::machine::{Observer, Opcode, Word};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Directions {
+    pub taken: bool,
+    pub not_taken: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    executed: BTreeSet<Word>,
+    branches: BTreeMap<Word, Directions>,
+}
+
+impl Observer for Coverage {
+    fn on_execute(&mut self, ip: Word, _op: Opcode) {
+        self.executed.insert(ip);
+    }
+
+    fn on_branch(&mut self, ip: Word, taken: bool) {
+        let directions = self.branches.entry(ip).or_default();
+        if taken {
+            directions.taken = true;
+        } else {
+            directions.not_taken = true;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub instructions: usize,
+    pub covered_instructions: usize,
+    pub branch_directions: usize,
+    pub covered_directions: usize,
+}
+
+fn percent(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "instructions: {}/{} ({:.1}%), branch directions: {}/{} ({:.1}%)",
+            self.covered_instructions,
+            self.instructions,
+            percent(self.covered_instructions, self.instructions),
+            self.covered_directions,
+            self.branch_directions,
+            percent(self.covered_directions, self.branch_directions),
+        )
+    }
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        self.executed.extend(&other.executed);
+        for (ip, other) in &other.branches {
+            let directions = self.branches.entry(*ip).or_default();
+            directions.taken |= other.taken;
+            directions.not_taken |= other.not_taken;
+        }
+    }
+
+    pub fn is_executed(&self, address: Word) -> bool {
+        self.executed.contains(&address)
+    }
+
+    pub fn directions(&self, address: Word) -> Directions {
+        self.branches.get(&address).copied().unwrap_or_default()
+    }
+
+    // Disassembly of the program, kept aligned with the executed addresses so
+    // operands of executed instructions never start a new instruction. `isa`
+    // should be the one the program ran with, so custom opcodes decode.
+    pub fn listing(&self, prog: &[Word], isa: &InstructionSet) -> Vec<Line> {
+        disassemble_isa(prog, isa, |instruction| {
+            self.is_executed(instruction.address)
+                || !instruction
+                    .cells()
+                    .skip(1)
+                    .any(|address| self.is_executed(address))
+        })
+    }
+
+    pub fn summary(&self, prog: &[Word], isa: &InstructionSet) -> Summary {
+        let mut summary = Summary {
+            instructions: 0,
+            covered_instructions: 0,
+            branch_directions: 0,
+            covered_directions: 0,
+        };
+        for line in self.listing(prog, isa) {
+            if let Line::Instruction(instruction) = line {
+                summary.instructions += 1;
+                summary.covered_instructions += self.is_executed(instruction.address) as usize;
+                if instruction.op.is_branch() {
+                    let directions = self.directions(instruction.address);
+                    summary.branch_directions += 2;
+                    summary.covered_directions +=
+                        directions.taken as usize + directions.not_taken as usize;
+                }
+            }
+        }
+        summary
+    }
+
+    pub fn annotate(&self, prog: &[Word], isa: &InstructionSet) -> String {
+        let mut out = String::new();
+        for line in self.listing(prog, isa) {
+            let address = line.address();
+            let (marker, branch) = match &line {
+                Line::Instruction(instruction) => {
+                    let marker = if self.is_executed(address) { '*' } else { '!' };
+                    let branch = if instruction.op.is_branch() {
+                        let directions = self.directions(address);
+                        format!(
+                            "[{}{}]",
+                            if directions.taken { 'T' } else { '-' },
+                            if directions.not_taken { 'F' } else { '-' }
+                        )
+                    } else {
+                        String::new()
+                    };
+                    (marker, branch)
+                }
+                // executed data means the program patched this cell at runtime
+                Line::Data(_, _) if self.is_executed(address) => ('*', String::new()),
+                Line::Data(_, _) => (' ', String::new()),
+            };
+            writeln!(out, "{:>6} {} {:<4} {}", address, marker, branch, line).unwrap();
+        }
+        writeln!(out, "{}", self.summary(prog, isa)).unwrap();
+        out
+    }
+}
+
+#[test]
+fn test_coverage_across_runs() {
+    use crate::{BufIo, Machine};
+
+    // outputs 1 if input equals 8, else 0
+    let prog = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+
+    let mut coverage = Coverage::new();
+    Machine::new(prog.clone()).execute_observed(&mut BufIo::new(vec![8]), &mut coverage);
+    assert_eq!(
+        coverage.directions(2),
+        Directions {
+            taken: true,
+            not_taken: false
+        }
+    );
+    let isa = InstructionSet::new();
+    assert_eq!(coverage.summary(&prog, &isa).covered_directions, 1);
+
+    Machine::new(prog.clone()).execute_observed(&mut BufIo::new(vec![3]), &mut coverage);
+    let summary = coverage.summary(&prog, &isa);
+    assert_eq!(summary.instructions, 4);
+    assert_eq!(summary.covered_instructions, 4);
+    assert_eq!(summary.branch_directions, 2);
+    assert_eq!(summary.covered_directions, 2);
+}
+
+#[test]
+fn test_coverage_custom_opcodes() {
+    use crate::{BufIo, CustomOp, Machine};
+
+    let isa = InstructionSet::new()
+        .with(42, CustomOp::new("dbg", 1, &[], |_| {}))
+        .unwrap();
+    let prog = vec![42, 3, 99, 7];
+    let mut coverage = Coverage::new();
+    Machine::with_instruction_set(prog.clone(), isa.clone())
+        .execute_observed(&mut BufIo::new(vec![]), &mut coverage);
+
+    let summary = coverage.summary(&prog, &isa);
+    assert_eq!(summary.instructions, 2);
+    assert_eq!(summary.covered_instructions, 2);
+    assert!(coverage.annotate(&prog, &isa).contains("dbg"));
+}
@@ -0,0 +1,239 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::machine::Word;
+
+// Handler for a range of memory mapped into a `Machine`. Offsets are relative
+// to the start of the mapped range, `cycles` is the machine's cycle counter at
+// the time of the access. `box_clone` gives a cloned machine its own copy.
+pub trait Device {
+    fn read(&mut self, offset: Word, cycles: u64) -> Word;
+    fn write(&mut self, _offset: Word, _value: Word, _cycles: u64) {}
+
+    // Number of cells the device can back, `None` for any range.
+    fn size(&self) -> Option<Word> {
+        None
+    }
+
+    fn box_clone(&self) -> Box<dyn Device>;
+}
+
+impl Device for Box<dyn Device> {
+    fn read(&mut self, offset: Word, cycles: u64) -> Word {
+        (**self).read(offset, cycles)
+    }
+
+    fn write(&mut self, offset: Word, value: Word, cycles: u64) {
+        (**self).write(offset, value, cycles)
+    }
+
+    fn size(&self) -> Option<Word> {
+        (**self).size()
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        (**self).box_clone()
+    }
+}
+
+pub(crate) struct Mapping {
+    pub range: Range<Word>,
+    pub device: Rc<RefCell<dyn Device>>,
+}
+
+// A copy of the device state, not another handle to it.
+impl Clone for Mapping {
+    fn clone(&self) -> Self {
+        let device: Rc<RefCell<dyn Device>> =
+            Rc::new(RefCell::new(self.device.borrow().box_clone()));
+        Self {
+            range: self.range.clone(),
+            device,
+        }
+    }
+}
+
+// Reads the number of cycles since it was last written, writing a value sets
+// the counter to it.
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    start: u64,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Device for Timer {
+    fn read(&mut self, _offset: Word, cycles: u64) -> Word {
+        cycles.wrapping_sub(self.start) as Word
+    }
+
+    fn write(&mut self, _offset: Word, value: Word, cycles: u64) {
+        self.start = cycles.wrapping_sub(value as u64);
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+// xorshift64, every read yields a new non-negative number, writing reseeds.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+}
+
+impl Device for Rng {
+    fn read(&mut self, _offset: Word, _cycles: u64) -> Word {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 1) as Word
+    }
+
+    fn write(&mut self, _offset: Word, value: Word, _cycles: u64) {
+        *self = Self::new(value as u64);
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+// Row-major grid of pixels, one cell per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Word>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    pub fn len(&self) -> Word {
+        self.pixels.len() as Word
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Word] {
+        &self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Word {
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl Device for Framebuffer {
+    fn read(&mut self, offset: Word, _cycles: u64) -> Word {
+        self.pixels[offset as usize]
+    }
+
+    fn write(&mut self, offset: Word, value: Word, _cycles: u64) {
+        self.pixels[offset as usize] = value;
+    }
+
+    fn size(&self) -> Option<Word> {
+        Some(self.len())
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+impl fmt::Display for Framebuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for pixel in row {
+                write!(f, "{}", if *pixel == 0 { ' ' } else { '#' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_mapped_devices() {
+    use crate::{BufIo, Machine};
+
+    let prog = vec![
+        1101, 7, 0, 2001, 1, 1000, 1000, 2002, 4, 1001, 1101, 0, 5, 1000, 4, 1000, 99,
+    ];
+    let mut m = Machine::new(prog);
+    m.map_device(1000..1001, Timer::new()).unwrap();
+    m.map_device(1001..1002, Rng::new(42)).unwrap();
+    let screen = m.map_device(2000..2004, Framebuffer::new(2, 2)).unwrap();
+    assert!(m.map_device(2003..2010, Timer::new()).is_err());
+    assert!(m.map_device(3000..3005, Framebuffer::new(2, 2)).is_err());
+
+    struct Writes(Vec<Word>);
+    impl crate::Observer for Writes {
+        fn on_write(&mut self, _machine: &Machine, address: Word, _old: Word) {
+            self.0.push(address);
+        }
+    }
+
+    let mut io = BufIo::new(vec![]);
+    let mut writes = Writes(Vec::new());
+    m.execute_observed(&mut io, &mut writes);
+    assert_eq!(writes.0, &[2001, 2002, 1000]);
+    assert_eq!(io.output(), &[Rng::new(42).read(0, 0), 6]);
+    assert_eq!(screen.borrow().pixels(), &[0, 7, 2, 0]);
+    assert_eq!(screen.borrow().to_string(), " #\n# \n");
+    assert_eq!(m.memory().len(), 17);
+}
+
+#[test]
+fn test_cloned_devices_are_independent() {
+    use crate::{BufIo, Machine};
+
+    // outputs two random numbers
+    let prog = vec![4, 100, 4, 100, 99];
+    let mut m = Machine::new(prog);
+    let rng = m.map_device(100..101, Rng::new(7)).unwrap();
+    let mut branch = m.clone();
+
+    let mut io = BufIo::new(vec![]);
+    m.execute(&mut io);
+    let mut branch_io = BufIo::new(vec![]);
+    branch.execute(&mut branch_io);
+    assert_eq!(io.output(), branch_io.output());
+    assert_ne!(io.output()[0], io.output()[1]);
+
+    // only the original moved the handed out device on
+    let mut fresh = Rng::new(7);
+    fresh.read(0, 0);
+    fresh.read(0, 0);
+    assert_eq!(rng.borrow_mut().read(0, 0), fresh.read(0, 0));
+}
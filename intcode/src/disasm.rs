@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::extension::InstructionSet;
+use crate::machine::{Opcode, ParamMode, Word};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: Word,
+    pub op: Opcode,
+    pub modes: [ParamMode; 3],
+    pub params: Vec<Word>,
+}
+
+impl Instruction {
+    pub fn decode(mem: &[Word], address: Word) -> Option<Instruction> {
+        Self::decode_with(mem, address, &InstructionSet::new())
+    }
+
+    pub fn decode_with(mem: &[Word], address: Word, isa: &InstructionSet) -> Option<Instruction> {
+        let start = address as usize;
+        let (op, modes) = isa.decode(*mem.get(start)?)?;
+        let params = mem.get(start + 1..start + 1 + op.arity())?.to_vec();
+        Some(Instruction {
+            address,
+            op,
+            modes,
+            params,
+        })
+    }
+
+    pub fn size(&self) -> Word {
+        1 + self.params.len() as Word
+    }
+
+    pub fn cells(&self) -> std::ops::Range<Word> {
+        self.address..self.address + self.size()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.op.mnemonic())?;
+        for (idx, (param, mode)) in self.params.iter().zip(&self.modes).enumerate() {
+            let separator = if idx == 0 { " " } else { ", " };
+            match mode {
+                ParamMode::Pointer => write!(f, "{}[{}]", separator, param)?,
+                ParamMode::Immediate => write!(f, "{}{}", separator, param)?,
+                ParamMode::Relative => write!(f, "{}[rb{:+}]", separator, param)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Instruction(Instruction),
+    Data(Word, Word),
+}
+
+impl Line {
+    pub fn address(&self) -> Word {
+        match self {
+            Line::Instruction(instruction) => instruction.address,
+            Line::Data(address, _) => *address,
+        }
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Instruction(instruction) => write!(f, "{}", instruction),
+            Line::Data(_, value) => write!(f, "data {}", value),
+        }
+    }
+}
+
+// Linear sweep over the memory. Cells which do not decode into a valid
+// instruction are listed as data.
+pub fn disassemble(mem: &[Word]) -> Vec<Line> {
+    disassemble_with(mem, |_| true)
+}
+
+// Like `disassemble`, but only accepts instructions for which `accept` returns
+// true, so a caller with extra knowledge (e.g. executed addresses) can keep the
+// sweep aligned to real code.
+pub fn disassemble_with(mem: &[Word], accept: impl FnMut(&Instruction) -> bool) -> Vec<Line> {
+    disassemble_isa(mem, &InstructionSet::new(), accept)
+}
+
+// Like `disassemble_with`, decoding custom opcodes of `isa` as well.
+pub fn disassemble_isa(
+    mem: &[Word],
+    isa: &InstructionSet,
+    mut accept: impl FnMut(&Instruction) -> bool,
+) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+    while (address as usize) < mem.len() {
+        match Instruction::decode_with(mem, address, isa).filter(|i| accept(i)) {
+            Some(instruction) => {
+                address += instruction.size();
+                lines.push(Line::Instruction(instruction));
+            }
+            None => {
+                lines.push(Line::Data(address, mem[address as usize]));
+                address += 1;
+            }
+        }
+    }
+    lines
+}
+
+#[test]
+fn test_disassemble() {
+    let prog = vec![109, 1, 204, -1, 1001, 100, 1, 100, 99, 7];
+    let listing: Vec<String> = disassemble(&prog).iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        listing,
+        &[
+            "arb 1",
+            "out [rb-1]",
+            "add [100], 1, [100]",
+            "halt",
+            "data 7"
+        ]
+    );
+}
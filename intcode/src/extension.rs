@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use crate::machine::{decode, Op, Opcode, ParamMode, Word};
+use crate::DynResult;
+
+// Arguments of a custom instruction. `args` holds the values of the read
+// parameters and `results` one slot per written parameter, both in parameter
+// order. Setting `jump` moves the ip there instead of past the instruction.
+pub struct Call<'a> {
+    pub args: &'a [Word],
+    pub results: &'a mut [Word],
+    pub jump: Option<Word>,
+}
+
+#[derive(Clone)]
+pub struct CustomOp {
+    name: &'static str,
+    arity: usize,
+    writes: Vec<usize>,
+    handler: Rc<dyn Fn(&mut Call)>,
+}
+
+impl CustomOp {
+    pub fn new(
+        name: &'static str,
+        arity: usize,
+        writes: &[usize],
+        handler: impl Fn(&mut Call) + 'static,
+    ) -> Self {
+        Self {
+            name,
+            arity,
+            writes: writes.to_vec(),
+            handler: Rc::new(handler),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    pub fn writes(&self) -> &[usize] {
+        &self.writes
+    }
+
+    pub(crate) fn call(&self, call: &mut Call) {
+        (self.handler)(call)
+    }
+}
+
+// The standard opcodes plus any registered custom ones. Builtin opcodes cannot
+// be overridden, everything not registered still decodes as invalid.
+#[derive(Clone, Default)]
+pub struct InstructionSet {
+    custom: BTreeMap<Word, CustomOp>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, opcode: Word, op: CustomOp) -> DynResult<()> {
+        if !(1..100).contains(&opcode) {
+            return Err(format!("Opcode {} does not fit in two digits", opcode).into());
+        }
+        if let Ok(builtin) = Op::try_from(opcode) {
+            return Err(format!("Opcode {} is taken by {}", opcode, builtin.mnemonic()).into());
+        }
+        if let Some(existing) = self.custom.get(&opcode) {
+            return Err(format!("Opcode {} is taken by {}", opcode, existing.name).into());
+        }
+        if op.arity > 3 {
+            return Err(format!("{} has {} parameters, at most 3 fit", op.name, op.arity).into());
+        }
+        if let Some(param) = op.writes.iter().find(|param| **param >= op.arity) {
+            return Err(format!("{} writes to missing parameter {}", op.name, param).into());
+        }
+        self.custom.insert(opcode, op);
+        Ok(())
+    }
+
+    pub fn with(mut self, opcode: Word, op: CustomOp) -> DynResult<Self> {
+        self.register(opcode, op)?;
+        Ok(self)
+    }
+
+    pub fn get(&self, opcode: Word) -> Option<&CustomOp> {
+        self.custom.get(&opcode)
+    }
+
+    pub fn decode(&self, op_byte: Word) -> Option<(Opcode, [ParamMode; 3])> {
+        if let Ok((op, modes)) = decode(op_byte) {
+            return Some((Opcode::Builtin(op), modes));
+        }
+        let code = op_byte % 100;
+        let op = self.custom.get(&code)?;
+        let mut modes = [ParamMode::Pointer; 3];
+        let mut divisor = 100;
+        for mode in &mut modes {
+            *mode = ParamMode::try_from(op_byte / divisor % 10).ok()?;
+            divisor *= 10;
+        }
+        Some((
+            Opcode::Custom {
+                code,
+                arity: op.arity,
+                name: op.name,
+            },
+            modes,
+        ))
+    }
+}
+
+#[test]
+fn test_custom_opcodes() {
+    use crate::machine::{Fault, Machine};
+    use std::cell::RefCell;
+
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let log = printed.clone();
+    let isa = InstructionSet::new()
+        .with(
+            42,
+            CustomOp::new("dbg", 1, &[], move |call| {
+                log.borrow_mut().push(call.args[0])
+            }),
+        )
+        .unwrap()
+        .with(
+            43,
+            CustomOp::new("max", 3, &[2], |call| {
+                call.results[0] = call.args[0].max(call.args[1])
+            }),
+        )
+        .unwrap();
+
+    let prog = vec![1143, 3, 9, 8, 42, 8, 99, 0, 0];
+    let mut m = Machine::with_instruction_set(prog.clone(), isa.clone());
+    m.execute(&mut crate::BufIo::new(vec![]));
+    assert_eq!(*printed.borrow(), &[9]);
+    assert_eq!(m.read_mem_at(8), 9);
+
+    let listing: Vec<String> = crate::disasm::disassemble_isa(&prog, &isa, |_| true)
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    assert_eq!(
+        listing,
+        &["max 3, 9, [8]", "dbg [8]", "halt", "data 0", "data 0"]
+    );
+
+    let mut m = Machine::with_instruction_set(vec![42, 0, 44, 99], isa);
+    let mut io = crate::BufIo::new(vec![]);
+    assert_eq!(
+        m.try_step(&mut io, &mut ()).unwrap(),
+        crate::StepResult::Continue
+    );
+    assert_eq!(
+        m.try_step(&mut io, &mut ()),
+        Err(Fault::InvalidInstruction {
+            ip: 2,
+            instruction: 44
+        })
+    );
+}
+
+#[test]
+fn test_register_conflicts() {
+    let mut isa = InstructionSet::new();
+    let nop = || CustomOp::new("nop", 0, &[], |_| {});
+    assert!(isa.register(1, nop()).is_err());
+    assert!(isa.register(100, nop()).is_err());
+    assert!(isa
+        .register(20, CustomOp::new("bad", 1, &[1], |_| {}))
+        .is_err());
+    assert!(isa.register(20, nop()).is_ok());
+    assert!(isa.register(20, nop()).is_err());
+}
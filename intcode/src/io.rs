@@ -38,26 +38,129 @@ impl Io for StdIo {
     }
 }
 
+// Text based Io: input is read from stdin line by line and fed as character
+// codes (including the newline), output values in the ASCII range are printed
+// as characters, anything else as a number on its own line.
+#[derive(Default)]
+pub struct AsciiIo {
+    pending: VecDeque<Word>,
+}
+
+impl AsciiIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Io for AsciiIo {
+    fn read_in(&mut self) -> Option<Word> {
+        if self.pending.is_empty() {
+            let mut input = String::new();
+            match std::io::stdin().read_line(&mut input) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    let line = input.trim_end_matches(['\n', '\r']);
+                    self.pending.extend(line.bytes().map(Word::from));
+                    self.pending.push_back(Word::from(b'\n'));
+                }
+                Err(error) => {
+                    panic!("Error reading input: {}", error);
+                }
+            }
+        }
+        self.pending.pop_front()
+    }
+
+    fn write_out(&mut self, data: Word) -> bool {
+        match data {
+            0..=127 => print!("{}", data as u8 as char),
+            _ => println!("{}", data),
+        }
+        true
+    }
+}
+
 pub struct IoBuffer {
     inner: VecDeque<Word>,
+    // only written when the buffer is used directly as Io, pipes push to `inner`
+    output: VecDeque<Word>,
+}
+
+impl Default for IoBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl IoBuffer {
     pub fn new() -> Self {
         Self {
             inner: VecDeque::new(),
+            output: VecDeque::new(),
         }
     }
 
     pub fn with_data(data: &[Word]) -> Self {
         Self {
             inner: data.iter().copied().collect(),
+            output: VecDeque::new(),
         }
     }
 
+    pub fn push(&mut self, data: Word) {
+        self.inner.push_back(data);
+    }
+
+    pub fn pop(&mut self) -> Option<Word> {
+        self.inner.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<Word> {
+        self.inner.front().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = Word> + '_ {
+        self.inner.drain(..)
+    }
+
     pub fn into_inner(self) -> VecDeque<Word> {
         self.inner
     }
+
+    pub fn drain_output(&mut self) -> impl Iterator<Item = Word> + '_ {
+        self.output.drain(..)
+    }
+
+    pub fn into_output(self) -> VecDeque<Word> {
+        self.output
+    }
+}
+
+impl Extend<Word> for IoBuffer {
+    fn extend<I: IntoIterator<Item = Word>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+// Used directly as Io the machine reads the queued data and writes to a
+// separate output, so it never reads back its own writes.
+impl Io for IoBuffer {
+    fn read_in(&mut self) -> Option<Word> {
+        self.pop()
+    }
+
+    fn write_out(&mut self, data: Word) -> bool {
+        self.output.push_back(data);
+        true
+    }
 }
 
 pub struct PipedIo<'a> {
@@ -76,11 +179,11 @@ impl<'a> PipedIo<'a> {
 
 impl Io for PipedIo<'_> {
     fn read_in(&mut self) -> Option<Word> {
-        self.read_buf.inner.pop_front()
+        self.read_buf.pop()
     }
 
     fn write_out(&mut self, data: Word) -> bool {
-        self.write_buf.inner.push_back(data);
+        self.write_buf.push(data);
         true
     }
 }
@@ -100,6 +203,34 @@ impl BufIo {
         }
     }
 
+    pub fn push_input(&mut self, data: Word) {
+        self.input.push(data);
+    }
+
+    pub fn extend_input(&mut self, data: impl IntoIterator<Item = Word>) {
+        self.input.extend(data);
+    }
+
+    pub fn remaining_input(&self) -> &[Word] {
+        &self.input[self.read_pos..]
+    }
+
+    pub fn output(&self) -> &[Word] {
+        &self.output
+    }
+
+    pub fn peek_output(&self) -> Option<Word> {
+        self.output.first().copied()
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.output.len()
+    }
+
+    pub fn drain_output(&mut self) -> Vec<Word> {
+        std::mem::take(&mut self.output)
+    }
+
     pub fn into_output(self) -> Vec<Word> {
         self.output
     }
@@ -107,7 +238,7 @@ impl BufIo {
 
 impl Io for BufIo {
     fn read_in(&mut self) -> Option<Word> {
-        let out = self.input[self.read_pos];
+        let out = self.input.get(self.read_pos).copied()?;
         self.read_pos += 1;
         Some(out)
     }
@@ -117,3 +248,41 @@ impl Io for BufIo {
         true
     }
 }
+
+#[test]
+fn test_bufio_blocks_when_exhausted() {
+    use crate::{Machine, StepResult};
+
+    // echoes inputs until it reads a zero
+    let prog = vec![3, 9, 4, 9, 1005, 9, 0, 99, 0, 0];
+    let mut m = Machine::new(prog);
+    let mut io = BufIo::new(vec![4]);
+    let run = |m: &mut Machine, io: &mut BufIo| loop {
+        match m.step(io) {
+            StepResult::Continue => {}
+            result => return result,
+        }
+    };
+    assert_eq!(run(&mut m, &mut io), StepResult::IoBlocked);
+    assert_eq!(io.peek_output(), Some(4));
+    assert_eq!(io.drain_output(), &[4]);
+
+    io.extend_input(vec![5, 0]);
+    assert_eq!(run(&mut m, &mut io), StepResult::Halt);
+    assert_eq!(io.output(), &[5, 0]);
+    assert!(io.remaining_input().is_empty());
+}
+
+#[test]
+fn test_iobuffer_io() {
+    let mut buf = IoBuffer::with_data(&[1]);
+    assert_eq!(buf.read_in(), Some(1));
+    buf.write_out(2);
+    assert_eq!(buf.read_in(), None);
+    buf.extend(vec![3, 4]);
+    assert_eq!(buf.peek(), Some(3));
+    assert_eq!(buf.len(), 2);
+    assert_eq!(buf.drain().collect::<Vec<_>>(), &[3, 4]);
+    assert!(buf.is_empty());
+    assert_eq!(buf.drain_output().collect::<Vec<_>>(), &[2]);
+}
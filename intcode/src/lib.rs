@@ -1,14 +1,30 @@
+mod coverage;
+mod device;
+mod disasm;
+mod extension;
 mod io;
 mod machine;
+mod memdiff;
+mod search;
+mod selfmod;
+mod symbolic;
 
+pub use coverage::*;
+pub use device::*;
+pub use disasm::*;
+pub use extension::*;
 pub use io::*;
 pub use machine::*;
+pub use memdiff::*;
+pub use search::*;
+pub use selfmod::*;
+pub use symbolic::*;
 
 pub type DynResult<T> = Result<T, Box<dyn std::error::Error>>;
 
 pub fn parse_intcode(bytes: &[u8]) -> DynResult<Vec<Word>> {
     bytes
         .split(|c| *c as char == ',')
-        .map(|slice| -> DynResult<Word> { Ok(std::str::from_utf8(slice)?.parse()?) })
+        .map(|slice| -> DynResult<Word> { Ok(std::str::from_utf8(slice)?.trim().parse()?) })
         .collect()
 }
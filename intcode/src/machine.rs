@@ -1,14 +1,20 @@
 use num_enum::TryFromPrimitive;
+use std::cell::RefCell;
 use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
 
+use crate::device::*;
+use crate::extension::*;
 use crate::io::*;
 use crate::DynResult;
 
 pub type Word = i64;
 
 #[repr(i64)]
-#[derive(Debug, TryFromPrimitive, Clone, Copy)]
-enum Op {
+#[derive(Debug, TryFromPrimitive, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
     Add = 1,
     Mul = 2,
     IoRead = 3,
@@ -21,22 +27,160 @@ enum Op {
     Halt = 99,
 }
 
+impl Op {
+    pub fn arity(self) -> usize {
+        match self {
+            Op::Add | Op::Mul | Op::LessThan | Op::Equals => 3,
+            Op::JumpIfTrue | Op::JumpIfFalse => 2,
+            Op::IoRead | Op::IoWrite | Op::OffsetRel => 1,
+            Op::Halt => 0,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Mul => "mul",
+            Op::IoRead => "in",
+            Op::IoWrite => "out",
+            Op::JumpIfTrue => "jnz",
+            Op::JumpIfFalse => "jz",
+            Op::LessThan => "lt",
+            Op::Equals => "eq",
+            Op::OffsetRel => "arb",
+            Op::Halt => "halt",
+        }
+    }
+
+    pub fn is_branch(self) -> bool {
+        matches!(
+            self,
+            Op::JumpIfTrue | Op::JumpIfFalse | Op::LessThan | Op::Equals
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Builtin(Op),
+    Custom {
+        code: Word,
+        arity: usize,
+        name: &'static str,
+    },
+}
+
+impl Opcode {
+    pub fn arity(self) -> usize {
+        match self {
+            Opcode::Builtin(op) => op.arity(),
+            Opcode::Custom { arity, .. } => arity,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Builtin(op) => op.mnemonic(),
+            Opcode::Custom { name, .. } => name,
+        }
+    }
+
+    pub fn is_branch(self) -> bool {
+        match self {
+            Opcode::Builtin(op) => op.is_branch(),
+            Opcode::Custom { .. } => false,
+        }
+    }
+}
+
 #[repr(i64)]
-#[derive(Debug, TryFromPrimitive, Clone, Copy)]
-enum ParamMode {
+#[derive(Debug, TryFromPrimitive, Clone, Copy, PartialEq, Eq)]
+pub enum ParamMode {
     Pointer = 0,
     Immediate = 1,
     Relative = 2,
 }
 
+#[derive(Clone)]
 pub struct Machine {
     mem: Vec<Word>,
     ip: Word,
     rel: Word,
-    decoded: (Op, [ParamMode; 3]),
+    cycles: u64,
+    modes: [ParamMode; 3],
+    isa: Rc<InstructionSet>,
+    // clones get their own copy of the mapped devices, the handles returned by
+    // `map_device` keep pointing at the original's
+    devices: Vec<Mapping>,
+}
+
+pub trait Observer {
+    fn on_execute(&mut self, _ip: Word, _op: Opcode) {}
+    fn on_branch(&mut self, _ip: Word, _taken: bool) {}
+    // Called after the write, `machine.ip()` still points at the writer. For a
+    // write to a mapped device `old` is the plain memory under it.
+    fn on_write(&mut self, _machine: &Machine, _address: Word, _old: Word) {}
+}
+
+impl Observer for () {}
+
+impl<T: Observer + ?Sized> Observer for &mut T {
+    fn on_execute(&mut self, ip: Word, op: Opcode) {
+        (**self).on_execute(ip, op)
+    }
+
+    fn on_branch(&mut self, ip: Word, taken: bool) {
+        (**self).on_branch(ip, taken)
+    }
+
+    fn on_write(&mut self, machine: &Machine, address: Word, old: Word) {
+        (**self).on_write(machine, address, old)
+    }
+}
+
+impl<A: Observer, B: Observer> Observer for (A, B) {
+    fn on_execute(&mut self, ip: Word, op: Opcode) {
+        self.0.on_execute(ip, op);
+        self.1.on_execute(ip, op);
+    }
+
+    fn on_branch(&mut self, ip: Word, taken: bool) {
+        self.0.on_branch(ip, taken);
+        self.1.on_branch(ip, taken);
+    }
+
+    fn on_write(&mut self, machine: &Machine, address: Word, old: Word) {
+        self.0.on_write(machine, address, old);
+        self.1.on_write(machine, address, old);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    InvalidInstruction { ip: Word, instruction: Word },
+    ImmediateWrite { ip: Word },
+    NegativeAddress { ip: Word, address: Word },
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fault::InvalidInstruction { ip, instruction } => {
+                write!(f, "Invalid opcode: {}. IP: {}", instruction, ip)
+            }
+            Fault::ImmediateWrite { ip } => {
+                write!(f, "Cannot write to immediate value. IP: {}", ip)
+            }
+            Fault::NegativeAddress { ip, address } => {
+                write!(f, "Access to negative address {}. IP: {}", address, ip)
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl std::error::Error for Fault {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StepResult {
     Continue,
     IoBlocked,
@@ -56,67 +200,180 @@ impl StepResult {
     }
 }
 
+pub(crate) fn decode(op_byte: Word) -> DynResult<(Op, [ParamMode; 3])> {
+    let op_instruction = op_byte % 100;
+    let param_modes = [
+        ParamMode::try_from(op_byte / 100 % 10)?,
+        ParamMode::try_from(op_byte / 1000 % 10)?,
+        ParamMode::try_from(op_byte / 10000 % 10)?,
+    ];
+    let op = Op::try_from(op_instruction)?;
+    Ok((op, param_modes))
+}
+
 impl Machine {
     pub fn new(mem_data: Vec<Word>) -> Self {
+        Self::with_instruction_set(mem_data, InstructionSet::new())
+    }
+
+    pub fn with_instruction_set(mem_data: Vec<Word>, isa: InstructionSet) -> Self {
         Self {
             ip: 0,
             rel: 0,
+            cycles: 0,
             mem: mem_data,
-            decoded: (Op::Halt, [ParamMode::Pointer; 3]),
+            modes: [ParamMode::Pointer; 3],
+            isa: Rc::new(isa),
+            devices: Vec::new(),
         }
     }
 
+    // Accesses to `range` go to `device` instead of memory. Returns the shared
+    // handle, so the host can inspect the device while the machine runs.
+    pub fn map_device<D: Device + 'static>(
+        &mut self,
+        range: Range<Word>,
+        device: D,
+    ) -> DynResult<Rc<RefCell<D>>> {
+        let device = Rc::new(RefCell::new(device));
+        self.map_shared_device(range, device.clone())?;
+        Ok(device)
+    }
+
+    pub fn map_shared_device(
+        &mut self,
+        range: Range<Word>,
+        device: Rc<RefCell<dyn Device>>,
+    ) -> DynResult<()> {
+        if range.start < 0 || range.is_empty() {
+            return Err(format!("Invalid device range {:?}", range).into());
+        }
+        if let Some(size) = device.borrow().size() {
+            if range.end - range.start > size {
+                return Err(
+                    format!("Device range {:?} is larger than its {} cells", range, size).into(),
+                );
+            }
+        }
+        if let Some(mapping) = self
+            .devices
+            .iter()
+            .find(|mapping| mapping.range.start < range.end && range.start < mapping.range.end)
+        {
+            return Err(format!("Device range {:?} overlaps {:?}", range, mapping.range).into());
+        }
+        self.devices.push(Mapping { range, device });
+        Ok(())
+    }
+
     #[inline]
-    fn try_fetch(&self) -> DynResult<(Op, [ParamMode; 3])> {
-        let op_byte = self.read_mem_at(self.ip);
-        let op_instruction = op_byte % 100;
-        let param_modes = [
-            ParamMode::try_from(op_byte / 100 % 10)?,
-            ParamMode::try_from(op_byte / 1000 % 10)?,
-            ParamMode::try_from(op_byte / 10000 % 10)?,
-        ];
-        let op = Op::try_from(op_instruction)?;
-        Ok((op, param_modes))
+    fn device_at(&self, address: Word) -> Option<&Mapping> {
+        if self.devices.is_empty() {
+            return None;
+        }
+        self.devices
+            .iter()
+            .find(|mapping| mapping.range.contains(&address))
+    }
+
+    pub fn instruction_set(&self) -> &InstructionSet {
+        &self.isa
+    }
+
+    pub fn ip(&self) -> Word {
+        self.ip
+    }
+
+    pub fn rel(&self) -> Word {
+        self.rel
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub fn memory(&self) -> &[Word] {
+        &self.mem
     }
 
     #[inline]
-    fn fetch(&self) -> (Op, [ParamMode; 3]) {
-        self.try_fetch()
-            .unwrap_or_else(|_| panic!("Invalid opcode: {}", self.read_mem_at(self.ip)))
+    fn fetch(&self) -> Result<(Opcode, [ParamMode; 3]), Fault> {
+        let instruction = self.try_read_mem_at(self.ip)?;
+        self.isa
+            .decode(instruction)
+            .ok_or(Fault::InvalidInstruction {
+                ip: self.ip,
+                instruction,
+            })
     }
 
     #[inline]
-    fn get_param(&self, param: usize) -> Word {
+    fn get_param(&self, param: usize) -> Result<Word, Fault> {
         let address = self.ip + param as Word + 1;
-        self.read_mem_at(address)
+        self.try_read_mem_at(address)
     }
 
     #[inline]
-    fn read(&self, param: usize) -> Word {
-        let value = self.get_param(param);
-        match self.decoded.1[param] {
-            ParamMode::Pointer => self.read_mem_at(value),
-            ParamMode::Immediate => value,
-            ParamMode::Relative => self.read_mem_at(value + self.rel),
+    fn read(&self, param: usize) -> Result<Word, Fault> {
+        let value = self.get_param(param)?;
+        match self.modes[param] {
+            ParamMode::Pointer => self.try_read_mem_at(value),
+            ParamMode::Immediate => Ok(value),
+            ParamMode::Relative => self.try_read_mem_at(value + self.rel),
         }
     }
 
     #[inline]
-    fn write(&mut self, param: usize, val: Word) {
-        let read_addr = match self.decoded.1[param] {
-            ParamMode::Pointer => self.get_param(param),
-            ParamMode::Immediate => panic!("Cannot write to immediate value."),
-            ParamMode::Relative => self.get_param(param) + self.rel,
+    fn write(
+        &mut self,
+        param: usize,
+        val: Word,
+        observer: &mut impl Observer,
+    ) -> Result<(), Fault> {
+        let read_addr = match self.modes[param] {
+            ParamMode::Pointer => self.get_param(param)?,
+            ParamMode::Immediate => return Err(Fault::ImmediateWrite { ip: self.ip }),
+            ParamMode::Relative => self.get_param(param)? + self.rel,
         };
-        assert!(read_addr >= 0);
+        if read_addr < 0 {
+            return Err(self.negative_address(read_addr));
+        }
+        if let Some(mapping) = self.device_at(read_addr) {
+            let offset = read_addr - mapping.range.start;
+            mapping.device.borrow_mut().write(offset, val, self.cycles);
+            observer.on_write(self, read_addr, self.read_mem_at(read_addr));
+            return Ok(());
+        }
         let address = read_addr as usize;
 
         if self.mem.len() <= address {
             self.mem.resize(address + 1, 0);
         }
-        self.mem[address] = val;
+        let old = std::mem::replace(&mut self.mem[address], val);
+        observer.on_write(self, read_addr, old);
+        Ok(())
+    }
+
+    fn negative_address(&self, address: Word) -> Fault {
+        Fault::NegativeAddress {
+            ip: self.ip,
+            address,
+        }
+    }
+
+    #[inline]
+    fn try_read_mem_at(&self, address: Word) -> Result<Word, Fault> {
+        if address < 0 {
+            return Err(self.negative_address(address));
+        }
+        if let Some(mapping) = self.device_at(address) {
+            let offset = address - mapping.range.start;
+            return Ok(mapping.device.borrow_mut().read(offset, self.cycles));
+        }
+        Ok(self.mem.get(address as usize).copied().unwrap_or(0))
     }
 
+    // Plain memory, mapped devices are not consulted.
     #[inline]
     pub fn read_mem_at(&self, address: Word) -> Word {
         assert!(address >= 0);
@@ -124,8 +381,12 @@ impl Machine {
     }
 
     pub fn execute(&mut self, io: &mut impl Io) {
+        self.execute_observed(io, &mut ())
+    }
+
+    pub fn execute_observed(&mut self, io: &mut impl Io, observer: &mut impl Observer) {
         loop {
-            match self.step(io) {
+            match self.step_observed(io, observer) {
                 StepResult::Continue => {}
                 StepResult::Halt => break,
                 StepResult::IoBlocked => panic!("Execution blocked on IO. IP: {}", self.ip),
@@ -133,27 +394,61 @@ impl Machine {
         }
     }
 
+    // Runs until the machine halts or blocks on IO, the result is never
+    // `StepResult::Continue`.
+    pub fn run_until_blocked(&mut self, io: &mut impl Io) -> Result<StepResult, Fault> {
+        loop {
+            match self.try_step(io, &mut ())? {
+                StepResult::Continue => {}
+                result => return Ok(result),
+            }
+        }
+    }
+
     pub fn step(&mut self, io: &mut impl Io) -> StepResult {
-        self.decoded = self.fetch();
-        // println!("[{}, {}]: {:?}", self.ip, self.rel, self.decoded);
-        match self.decoded.0 {
+        self.step_observed(io, &mut ())
+    }
+
+    pub fn step_observed(&mut self, io: &mut impl Io, observer: &mut impl Observer) -> StepResult {
+        self.try_step(io, observer)
+            .unwrap_or_else(|fault| panic!("{}", fault))
+    }
+
+    pub fn try_step(
+        &mut self,
+        io: &mut impl Io,
+        observer: &mut impl Observer,
+    ) -> Result<StepResult, Fault> {
+        let (opcode, modes) = self.fetch()?;
+        self.modes = modes;
+        // println!("[{}, {}]: {:?}", self.ip, self.rel, opcode);
+        observer.on_execute(self.ip, opcode);
+        let op = match opcode {
+            Opcode::Builtin(op) => op,
+            Opcode::Custom { code, .. } => {
+                self.call_custom(code, observer)?;
+                self.cycles += 1;
+                return Ok(StepResult::Continue);
+            }
+        };
+        let result = match op {
             Op::Add => {
-                let a = self.read(0);
-                let b = self.read(1);
-                self.write(2, a + b);
+                let a = self.read(0)?;
+                let b = self.read(1)?;
+                self.write(2, a + b, observer)?;
                 self.ip += 4;
                 StepResult::Continue
             }
             Op::Mul => {
-                let a = self.read(0);
-                let b = self.read(1);
-                self.write(2, a * b);
+                let a = self.read(0)?;
+                let b = self.read(1)?;
+                self.write(2, a * b, observer)?;
                 self.ip += 4;
                 StepResult::Continue
             }
             Op::IoRead => {
                 if let Some(input) = io.read_in() {
-                    self.write(0, input);
+                    self.write(0, input, observer)?;
                     self.ip += 2;
                     StepResult::Continue
                 } else {
@@ -161,7 +456,7 @@ impl Machine {
                 }
             }
             Op::IoWrite => {
-                let a = self.read(0);
+                let a = self.read(0)?;
                 if io.write_out(a) {
                     self.ip += 2;
                     StepResult::Continue
@@ -170,103 +465,74 @@ impl Machine {
                 }
             }
             Op::JumpIfTrue => {
-                if self.read(0) != 0 {
-                    self.ip = self.read(1);
+                let taken = self.read(0)? != 0;
+                observer.on_branch(self.ip, taken);
+                if taken {
+                    self.ip = self.read(1)?;
                 } else {
                     self.ip += 3;
                 }
                 StepResult::Continue
             }
             Op::JumpIfFalse => {
-                if self.read(0) == 0 {
-                    self.ip = self.read(1);
+                let taken = self.read(0)? == 0;
+                observer.on_branch(self.ip, taken);
+                if taken {
+                    self.ip = self.read(1)?;
                 } else {
                     self.ip += 3;
                 }
                 StepResult::Continue
             }
             Op::LessThan => {
-                self.write(2, (self.read(0) < self.read(1)) as _);
+                let result = self.read(0)? < self.read(1)?;
+                observer.on_branch(self.ip, result);
+                self.write(2, result as _, observer)?;
                 self.ip += 4;
                 StepResult::Continue
             }
             Op::Equals => {
-                self.write(2, (self.read(0) == self.read(1)) as _);
+                let result = self.read(0)? == self.read(1)?;
+                observer.on_branch(self.ip, result);
+                self.write(2, result as _, observer)?;
                 self.ip += 4;
                 StepResult::Continue
             }
             Op::OffsetRel => {
-                let a = self.read(0);
+                let a = self.read(0)?;
                 self.rel += a;
                 self.ip += 2;
                 StepResult::Continue
             }
             Op::Halt => StepResult::Halt,
+        };
+        if let StepResult::Continue = result {
+            self.cycles += 1;
         }
+        Ok(result)
     }
-}
 
-#[allow(dead_code)]
-fn test_machine(prog: Vec<Word>, input: Vec<Word>) -> Vec<Word> {
-    let mut m = Machine::new(prog);
-    let mut io = BufIo::new(input);
-    m.execute(&mut io);
-    io.into_output()
-}
-
-#[test]
-fn test_pos_mode_io() {
-    let prog = vec![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
-
-    let out1 = test_machine(prog.clone(), vec![0]);
-    let out2 = test_machine(prog.clone(), vec![10]);
-    assert_eq!(out1, &[0]);
-    assert_eq!(out2, &[1]);
-}
-
-#[test]
-fn test_imm_mode_io() {
-    let prog = vec![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1];
-
-    let out1 = test_machine(prog.clone(), vec![0]);
-    let out2 = test_machine(prog.clone(), vec![10]);
-    assert_eq!(out1, &[0]);
-    assert_eq!(out2, &[1]);
-}
-
-#[test]
-fn test_jumps_larger() {
-    let prog = vec![
-        3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
-        1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20,
-        1105, 1, 46, 98, 99,
-    ];
-
-    assert_eq!(test_machine(prog.clone(), vec![0]), &[999]);
-    assert_eq!(test_machine(prog.clone(), vec![-15]), &[999]);
-    assert_eq!(test_machine(prog.clone(), vec![7]), &[999]);
-    assert_eq!(test_machine(prog.clone(), vec![8]), &[1000]);
-    assert_eq!(test_machine(prog.clone(), vec![9]), &[1001]);
-}
-#[test]
-fn test_quine() {
-    let prog = vec![
-        109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
-    ];
-    let out = test_machine(prog.clone(), vec![]);
-    assert_eq!(&out, &prog);
-}
-
-#[test]
-fn test_large_number_output() {
-    let prog = vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0];
-    let out = test_machine(prog, vec![]);
-    assert_eq!(&out, &[1219070632396864]);
-}
-
-#[test]
-fn test_large_number_code() {
-    let prog = vec![104, 1125899906842624, 99];
-    let out = test_machine(prog, vec![]);
-    assert_eq!(&out, &[1125899906842624]);
+    fn call_custom(&mut self, code: Word, observer: &mut impl Observer) -> Result<(), Fault> {
+        let isa = self.isa.clone();
+        let op = isa.get(code).expect("decoded custom opcode is registered");
+        let mut args = Vec::new();
+        for param in 0..op.arity() {
+            if !op.writes().contains(&param) {
+                args.push(self.read(param)?);
+            }
+        }
+        let mut results = vec![0; op.writes().len()];
+        let mut call = Call {
+            args: &args,
+            results: &mut results,
+            jump: None,
+        };
+        op.call(&mut call);
+        let jump = call.jump;
+        for (param, value) in op.writes().iter().zip(results) {
+            self.write(*param, value, observer)?;
+        }
+        self.ip = jump.unwrap_or(self.ip + 1 + op.arity() as Word);
+        Ok(())
+    }
 }
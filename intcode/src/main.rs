@@ -0,0 +1,249 @@
+use intcode::*;
+use std::collections::VecDeque;
+
+const USAGE: &str = "usage: intcode <program> [options]
+
+options:
+    --set ADDR=VALUE     patch memory before running, may be repeated
+    --input VALUES       comma separated inputs (a line of text with --ascii)
+    --input-file PATH    read inputs from a file
+    --stdin              read inputs from stdin once the given ones run out
+    --ascii              exchange input and output as text
+    --print ADDR         print a memory cell after the program halts
+    --coverage           print instruction and branch coverage
+    --annotate           print the program listing annotated with coverage
+    --self-modifying     report writes into already executed code
+    --memdiff FROM..TO   show the memory changed between two cycle counts";
+
+#[derive(Default)]
+struct Options {
+    program: Option<String>,
+    patches: Vec<(Word, Word)>,
+    inputs: VecDeque<Word>,
+    stdin: bool,
+    ascii: bool,
+    print: Vec<Word>,
+    coverage: bool,
+    annotate: bool,
+    self_modifying: bool,
+    memdiff: Option<(u64, u64)>,
+    help: bool,
+}
+
+fn parse_numbers(text: &str) -> DynResult<Vec<Word>> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|number| !number.is_empty())
+        .map(|number| Ok(number.parse()?))
+        .collect()
+}
+
+fn parse_args() -> DynResult<Options> {
+    let mut options = Options::default();
+    let mut raw_inputs = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--set" => {
+                let patch = value()?;
+                let mut split = patch.splitn(2, '=');
+                let address = split.next().unwrap_or("").parse()?;
+                let value = split
+                    .next()
+                    .ok_or_else(|| format!("Expected ADDR=VALUE, got {}", patch))?
+                    .parse()?;
+                options.patches.push((address, value));
+            }
+            "--input" => raw_inputs.push((value()?, true)),
+            "--input-file" => raw_inputs.push((std::fs::read_to_string(value()?)?, false)),
+            "--stdin" => options.stdin = true,
+            "--ascii" => options.ascii = true,
+            "--print" => {
+                let address = value()?.parse()?;
+                if address < 0 {
+                    return Err(format!("Cannot print negative address {}", address).into());
+                }
+                options.print.push(address);
+            }
+            "--coverage" => options.coverage = true,
+            "--annotate" => options.annotate = true,
+            "--self-modifying" => options.self_modifying = true,
+            "--memdiff" => {
+                let range = value()?;
+                let mut split = range.splitn(2, "..");
+                let from = split.next().unwrap_or("").parse()?;
+                let to = split
+                    .next()
+                    .ok_or_else(|| format!("Expected FROM..TO, got {}", range))?
+                    .parse()?;
+                options.memdiff = Some((from, to));
+            }
+            "--help" | "-h" => options.help = true,
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag).into()),
+            _ if options.program.is_none() => options.program = Some(arg),
+            _ => return Err(format!("Unexpected argument {}", arg).into()),
+        }
+    }
+
+    // inputs are converted last, so --ascii may come after them
+    for (text, is_line) in raw_inputs {
+        if options.ascii {
+            options.inputs.extend(text.bytes().map(Word::from));
+            if is_line {
+                options.inputs.push_back(Word::from(b'\n'));
+            }
+        } else {
+            options.inputs.extend(parse_numbers(&text)?);
+        }
+    }
+    if options.inputs.is_empty() {
+        options.stdin = true;
+    }
+    Ok(options)
+}
+
+struct CliIo {
+    inputs: VecDeque<Word>,
+    stdin: bool,
+    ascii: Option<AsciiIo>,
+}
+
+// Unlike StdIo, which asks again, the end of stdin or a line that is not a
+// number ends the input and leaves the program blocked.
+fn read_stdin_number() -> Option<Word> {
+    let mut line = String::new();
+    match std::io::stdin().read_line(&mut line) {
+        Ok(0) => None,
+        Ok(_) => match line.trim().parse() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                eprintln!("Invalid input {:?}: {}", line.trim(), error);
+                None
+            }
+        },
+        Err(error) => panic!("Error reading input: {}", error),
+    }
+}
+
+impl Io for CliIo {
+    fn read_in(&mut self) -> Option<Word> {
+        if let Some(input) = self.inputs.pop_front() {
+            return Some(input);
+        }
+        if !self.stdin {
+            return None;
+        }
+        match &mut self.ascii {
+            Some(ascii) => ascii.read_in(),
+            None => read_stdin_number(),
+        }
+    }
+
+    fn write_out(&mut self, data: Word) -> bool {
+        match &mut self.ascii {
+            Some(ascii) => ascii.write_out(data),
+            None => StdIo.write_out(data),
+        }
+    }
+}
+
+fn run(machine: &mut Machine, io: &mut CliIo, observer: &mut impl Observer) -> DynResult<()> {
+    loop {
+        match machine.try_step(io, observer)? {
+            StepResult::Continue => {}
+            StepResult::Halt => return Ok(()),
+            StepResult::IoBlocked => {
+                return Err(format!("Program blocked on input. IP: {}", machine.ip()).into())
+            }
+        }
+    }
+}
+
+fn main() -> DynResult<()> {
+    let options = match parse_args() {
+        Ok(options) if options.help => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Ok(Options { program: None, .. }) => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let program = options.program.as_ref().unwrap();
+    let mut mem = parse_intcode(&std::fs::read(program)?)?;
+    let prog = mem.clone();
+
+    for (address, value) in &options.patches {
+        if *address < 0 {
+            return Err(format!("Cannot patch negative address {}", address).into());
+        }
+        let address = *address as usize;
+        if mem.len() <= address {
+            mem.resize(address + 1, 0);
+        }
+        mem[address] = *value;
+    }
+
+    let ascii = if options.ascii {
+        Some(AsciiIo::new())
+    } else {
+        None
+    };
+    let mut io = CliIo {
+        inputs: options.inputs,
+        stdin: options.stdin,
+        ascii,
+    };
+    let mut machine = Machine::new(mem);
+    if let Some((from, to)) = options.memdiff {
+        let mut stack = StackExtent::new(&machine);
+        let before = snapshot_at(&mut machine, &mut io, from, &mut stack)?;
+        let mut stack = StackExtent::new(&before);
+        let after = snapshot_at(&mut machine, &mut io, to, &mut stack)?;
+        println!("Cycles {} to {}:", before.cycles(), after.cycles());
+        let diff = MemDiff::between(&before, &after);
+        print!("{}", diff);
+        let view = MemoryView::new(after.memory())
+            .code(0..prog.len() as Word)
+            .stack(stack.range())
+            .written(&diff);
+        print!("{}", view);
+        return Ok(());
+    }
+    // only the requested observers are attached, plain runs pay for none
+    let mut coverage = Coverage::new();
+    let mut self_mod = SelfModDetector::new();
+    match (options.coverage || options.annotate, options.self_modifying) {
+        (false, false) => run(&mut machine, &mut io, &mut ())?,
+        (true, false) => run(&mut machine, &mut io, &mut coverage)?,
+        (false, true) => run(&mut machine, &mut io, &mut self_mod)?,
+        (true, true) => run(&mut machine, &mut io, &mut (&mut coverage, &mut self_mod))?,
+    }
+
+    for address in options.print {
+        println!("[{}] = {}", address, machine.read_mem_at(address));
+    }
+    if options.coverage {
+        println!(
+            "Coverage: {}",
+            coverage.summary(&prog, machine.instruction_set())
+        );
+    }
+    if options.annotate {
+        print!("{}", coverage.annotate(&prog, machine.instruction_set()));
+    }
+    if options.self_modifying {
+        print!("{}", self_mod.report());
+    }
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+
+use crate::io::Io;
+use crate::machine::{Fault, Machine, StepResult, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub address: Word,
+    pub old: Word,
+    pub new: Word,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedRange {
+    pub start: Word,
+    pub old: Vec<Word>,
+    pub new: Vec<Word>,
+}
+
+impl ChangedRange {
+    pub fn addresses(&self) -> Range<Word> {
+        self.start..self.start + self.old.len() as Word
+    }
+}
+
+fn join(values: &[Word]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for ChangedRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let addresses = self.addresses();
+        if addresses.end - addresses.start == 1 {
+            write!(f, "[{}]", addresses.start)?;
+        } else {
+            write!(f, "[{}..{}]", addresses.start, addresses.end)?;
+        }
+        write!(f, " {} -> {}", join(&self.old), join(&self.new))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemDiff {
+    changes: Vec<Change>,
+}
+
+impl MemDiff {
+    pub fn between(before: &Machine, after: &Machine) -> Self {
+        Self::of_memory(before.memory(), after.memory())
+    }
+
+    // Memory past the end of either slice reads as zero, like in `Machine`.
+    pub fn of_memory(before: &[Word], after: &[Word]) -> Self {
+        let len = before.len().max(after.len());
+        let changes = (0..len)
+            .filter_map(|address| {
+                let old = before.get(address).copied().unwrap_or(0);
+                let new = after.get(address).copied().unwrap_or(0);
+                if old == new {
+                    None
+                } else {
+                    Some(Change {
+                        address: address as Word,
+                        old,
+                        new,
+                    })
+                }
+            })
+            .collect();
+        Self { changes }
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = Word> + '_ {
+        self.changes.iter().map(|change| change.address)
+    }
+
+    pub fn ranges(&self) -> Vec<ChangedRange> {
+        let mut ranges: Vec<ChangedRange> = Vec::new();
+        for change in &self.changes {
+            match ranges.last_mut() {
+                Some(range) if range.addresses().end == change.address => {
+                    range.old.push(change.old);
+                    range.new.push(change.new);
+                }
+                _ => ranges.push(ChangedRange {
+                    start: change.address,
+                    old: vec![change.old],
+                    new: vec![change.new],
+                }),
+            }
+        }
+        ranges
+    }
+}
+
+impl fmt::Display for MemDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for range in self.ranges() {
+            writeln!(f, "{}", range)?;
+        }
+        Ok(())
+    }
+}
+
+// Lowest and highest relative base seen while running, the cells between them
+// hold the relative-base stack of the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackExtent {
+    low: Word,
+    high: Word,
+}
+
+impl StackExtent {
+    pub fn new(machine: &Machine) -> Self {
+        Self {
+            low: machine.rel(),
+            high: machine.rel(),
+        }
+    }
+
+    pub fn update(&mut self, machine: &Machine) {
+        self.low = self.low.min(machine.rel());
+        self.high = self.high.max(machine.rel());
+    }
+
+    pub fn range(&self) -> Range<Word> {
+        self.low..self.high + 1
+    }
+}
+
+// Steps the machine until it has run `cycles` cycles in total, halts or blocks
+// on IO, and returns a copy of it at that point. The relative bases it goes
+// through are added to `stack`.
+pub fn snapshot_at(
+    machine: &mut Machine,
+    io: &mut impl Io,
+    cycles: u64,
+    stack: &mut StackExtent,
+) -> Result<Machine, Fault> {
+    while machine.cycles() < cycles {
+        let result = machine.try_step(io, &mut ())?;
+        stack.update(machine);
+        if result != StepResult::Continue {
+            break;
+        }
+    }
+    Ok(machine.clone())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Code,
+    Stack,
+    Written,
+    Data,
+}
+
+impl Region {
+    fn marker(self) -> char {
+        match self {
+            Region::Code => 'c',
+            Region::Stack => 's',
+            Region::Written => '*',
+            Region::Data => ' ',
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Region::Code => "\x1b[34m",
+            Region::Stack => "\x1b[33m",
+            Region::Written => "\x1b[1;31m",
+            Region::Data => "",
+        }
+    }
+}
+
+// Hex-dump style listing of the memory, 8 words per row. Written cells take
+// precedence over the stack, which takes precedence over the code region.
+pub struct MemoryView<'a> {
+    mem: &'a [Word],
+    code: Range<Word>,
+    stack: Range<Word>,
+    written: BTreeSet<Word>,
+    color: bool,
+}
+
+impl<'a> MemoryView<'a> {
+    pub fn new(mem: &'a [Word]) -> Self {
+        Self {
+            mem,
+            code: 0..0,
+            stack: 0..0,
+            written: BTreeSet::new(),
+            color: false,
+        }
+    }
+
+    pub fn code(mut self, code: Range<Word>) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn stack(mut self, stack: Range<Word>) -> Self {
+        self.stack = stack;
+        self
+    }
+
+    pub fn written(mut self, diff: &MemDiff) -> Self {
+        self.written.extend(diff.addresses());
+        self
+    }
+
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn region(&self, address: Word) -> Region {
+        if self.written.contains(&address) {
+            Region::Written
+        } else if self.stack.contains(&address) {
+            Region::Stack
+        } else if self.code.contains(&address) {
+            Region::Code
+        } else {
+            Region::Data
+        }
+    }
+}
+
+impl fmt::Display for MemoryView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (row, chunk) in self.mem.chunks(8).enumerate() {
+            write!(f, "{:>6}:", row * 8)?;
+            for (column, value) in chunk.iter().enumerate() {
+                let region = self.region((row * 8 + column) as Word);
+                if self.color && region != Region::Data {
+                    write!(f, " {}{:>9}\x1b[0m", region.color(), value)?;
+                } else {
+                    write!(f, " {:>8}{}", value, region.marker())?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_diff_between_snapshots() {
+    use crate::BufIo;
+
+    let prog = vec![3, 11, 1001, 11, 1, 12, 1101, 2, 3, 13, 99, 0];
+    let mut m = Machine::new(prog);
+    let mut io = BufIo::new(vec![7]);
+    m.step(&mut io);
+    let before = m.clone();
+    m.execute(&mut io);
+
+    let diff = MemDiff::between(&before, &m);
+    assert_eq!(
+        diff.ranges(),
+        &[ChangedRange {
+            start: 12,
+            old: vec![0, 0],
+            new: vec![8, 5],
+        }]
+    );
+    assert_eq!(diff.to_string(), "[12..14] 0, 0 -> 8, 5\n");
+
+    let view = MemoryView::new(m.memory()).code(0..11).written(&diff);
+    assert_eq!(view.region(0), Region::Code);
+    assert_eq!(view.region(11), Region::Data);
+    assert_eq!(view.region(13), Region::Written);
+}
+
+#[test]
+fn test_snapshots_at_cycles() {
+    use crate::BufIo;
+
+    // moves the relative base up by 5 and back, writing through it each time
+    let prog = vec![109, 5, 21101, 1, 1, 20, 109, -3, 21101, 2, 2, 20, 99];
+    let mut m = Machine::new(prog);
+    let mut io = BufIo::new(vec![]);
+    let mut stack = StackExtent::new(&m);
+    let first = snapshot_at(&mut m, &mut io, 2, &mut stack).unwrap();
+    assert_eq!((first.cycles(), first.rel()), (2, 5));
+    let second = snapshot_at(&mut m, &mut io, 100, &mut stack).unwrap();
+    assert_eq!(second.rel(), 2);
+    assert_eq!(stack.range(), 0..6);
+
+    let diff = MemDiff::between(&first, &second);
+    assert_eq!(diff.to_string(), "[22] 0 -> 4\n");
+}
@@ -0,0 +1,230 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::io::BufIo;
+use crate::machine::{Fault, Machine, StepResult, Word};
+
+// Domain side of a search. Every move is fed to a clone of the parent's
+// machine as input, which then runs until it wants more input or halts. The
+// produced output decides the state of the child.
+pub trait Explore {
+    type State: Clone;
+    type Move: Clone;
+    type Key: Hash + Eq;
+
+    fn moves(&mut self, state: &Self::State) -> Vec<Self::Move>;
+    fn input(&mut self, mv: &Self::Move) -> Vec<Word>;
+    // Returning None prunes the branch.
+    fn update(
+        &mut self,
+        state: &Self::State,
+        mv: &Self::Move,
+        output: &[Word],
+    ) -> Option<Self::State>;
+    // States with equal keys are only expanded once (or again when reached
+    // cheaper during A*).
+    fn key(&self, state: &Self::State) -> Self::Key;
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    fn cost(&self, _mv: &Self::Move) -> u64 {
+        1
+    }
+
+    // Lower bound of the remaining cost, only used by A*.
+    fn heuristic(&self, _state: &Self::State) -> u64 {
+        0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOrder {
+    BreadthFirst,
+    DepthFirst,
+    AStar,
+}
+
+pub struct Found<E: Explore> {
+    pub moves: Vec<E::Move>,
+    pub state: E::State,
+    pub machine: Machine,
+    pub cost: u64,
+    pub expanded: usize,
+}
+
+struct Node<E: Explore> {
+    // taken once the node gets expanded
+    machine: Option<Machine>,
+    state: E::State,
+    parent: Option<(usize, E::Move)>,
+    cost: u64,
+    halted: bool,
+}
+
+enum Frontier {
+    Queue(VecDeque<usize>),
+    Stack(Vec<usize>),
+    Heap(BinaryHeap<Reverse<(u64, usize)>>),
+}
+
+impl Frontier {
+    fn new(order: SearchOrder) -> Self {
+        match order {
+            SearchOrder::BreadthFirst => Frontier::Queue(VecDeque::new()),
+            SearchOrder::DepthFirst => Frontier::Stack(Vec::new()),
+            SearchOrder::AStar => Frontier::Heap(BinaryHeap::new()),
+        }
+    }
+
+    fn push(&mut self, node: usize, priority: u64) {
+        match self {
+            Frontier::Queue(queue) => queue.push_back(node),
+            Frontier::Stack(stack) => stack.push(node),
+            Frontier::Heap(heap) => heap.push(Reverse((priority, node))),
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        match self {
+            Frontier::Queue(queue) => queue.pop_front(),
+            Frontier::Stack(stack) => stack.pop(),
+            Frontier::Heap(heap) => heap.pop().map(|Reverse((_, node))| node),
+        }
+    }
+}
+
+// Searches from `root` (typically a machine waiting for input) for a state
+// accepted by `Explore::is_goal`. Faults of any branch abort the search.
+pub fn search<E: Explore>(
+    root: Machine,
+    state: E::State,
+    explore: &mut E,
+    order: SearchOrder,
+) -> Result<Option<Found<E>>, Fault> {
+    let mut best = HashMap::new();
+    best.insert(explore.key(&state), 0);
+    let mut frontier = Frontier::new(order);
+    frontier.push(0, explore.heuristic(&state));
+    let mut nodes = vec![Node::<E> {
+        machine: Some(root),
+        state,
+        parent: None,
+        cost: 0,
+        halted: false,
+    }];
+    let mut expanded = 0;
+
+    while let Some(idx) = frontier.pop() {
+        let node = &mut nodes[idx];
+        if best.get(&explore.key(&node.state)) != Some(&node.cost) {
+            // reached cheaper by now
+            continue;
+        }
+        if explore.is_goal(&node.state) {
+            return Ok(Some(found(nodes, idx, expanded)));
+        }
+        if node.halted {
+            continue;
+        }
+        let machine = node.machine.take().expect("node expanded once");
+        let (state, cost) = (node.state.clone(), node.cost);
+        expanded += 1;
+
+        for mv in explore.moves(&state) {
+            let mut child = machine.clone();
+            let mut io = BufIo::new(explore.input(&mv));
+            let halted = child.run_until_blocked(&mut io)? == StepResult::Halt;
+            let child_state = match explore.update(&state, &mv, io.output()) {
+                Some(child_state) => child_state,
+                None => continue,
+            };
+            let child_cost = cost + explore.cost(&mv);
+            let key = explore.key(&child_state);
+            if best.get(&key).is_some_and(|known| *known <= child_cost) {
+                continue;
+            }
+            best.insert(key, child_cost);
+            frontier.push(nodes.len(), child_cost + explore.heuristic(&child_state));
+            nodes.push(Node {
+                machine: Some(child),
+                state: child_state,
+                parent: Some((idx, mv)),
+                cost: child_cost,
+                halted,
+            });
+        }
+    }
+    Ok(None)
+}
+
+fn found<E: Explore>(mut nodes: Vec<Node<E>>, goal: usize, expanded: usize) -> Found<E> {
+    let mut moves = Vec::new();
+    let mut idx = goal;
+    while let Some((parent, mv)) = nodes[idx].parent.take() {
+        moves.push(mv);
+        idx = parent;
+    }
+    moves.reverse();
+    let goal = nodes.swap_remove(goal);
+    Found {
+        moves,
+        state: goal.state,
+        machine: goal.machine.expect("goal not expanded"),
+        cost: goal.cost,
+        expanded,
+    }
+}
+
+// Adds up inputs of 3 or 4 and halts once the sum reaches exactly 10.
+#[cfg(test)]
+struct SumTo10;
+
+#[cfg(test)]
+impl Explore for SumTo10 {
+    type State = Word;
+    type Move = Word;
+    type Key = Word;
+
+    fn moves(&mut self, _state: &Word) -> Vec<Word> {
+        vec![3, 4]
+    }
+
+    fn input(&mut self, mv: &Word) -> Vec<Word> {
+        vec![*mv]
+    }
+
+    fn update(&mut self, _state: &Word, _mv: &Word, output: &[Word]) -> Option<Word> {
+        output.last().copied().filter(|sum| *sum <= 10)
+    }
+
+    fn key(&self, state: &Word) -> Word {
+        *state
+    }
+
+    fn is_goal(&self, state: &Word) -> bool {
+        *state == 10
+    }
+
+    fn heuristic(&self, state: &Word) -> u64 {
+        (10 - *state as u64).div_ceil(4)
+    }
+}
+
+#[test]
+fn test_search_strategies() {
+    let prog = vec![
+        3, 100, 1, 100, 101, 101, 4, 101, 1008, 101, 10, 102, 1005, 102, 18, 1105, 1, 0, 99,
+    ];
+    for order in [
+        SearchOrder::BreadthFirst,
+        SearchOrder::DepthFirst,
+        SearchOrder::AStar,
+    ] {
+        let found = search(Machine::new(prog.clone()), 0, &mut SumTo10, order)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.moves.iter().sum::<Word>(), 10);
+        assert_eq!(found.cost, 3);
+        assert_eq!(found.machine.read_mem_at(found.machine.ip()), 99);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::disasm::Instruction;
+use crate::machine::{Machine, Observer, Opcode, Word};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfModification {
+    pub writer_ip: Word,
+    pub address: Word,
+    pub old_value: Word,
+    pub new_value: Word,
+    pub instruction_address: Word,
+    pub old: Option<Instruction>,
+    pub new: Option<Instruction>,
+}
+
+impl SelfModification {
+    pub fn patches_opcode(&self) -> bool {
+        self.address == self.instruction_address
+    }
+}
+
+fn describe(instruction: &Option<Instruction>) -> String {
+    match instruction {
+        Some(instruction) => instruction.to_string(),
+        None => "<invalid>".to_string(),
+    }
+}
+
+impl fmt::Display for SelfModification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "IP {} wrote [{}] {} -> {}, instruction at {}: {} => {}",
+            self.writer_ip,
+            self.address,
+            self.old_value,
+            self.new_value,
+            self.instruction_address,
+            describe(&self.old),
+            describe(&self.new),
+        )
+    }
+}
+
+// Flags writes into cells that were already executed or decoded as part of an
+// executed instruction.
+#[derive(Debug, Clone, Default)]
+pub struct SelfModDetector {
+    code: BTreeMap<Word, Word>,
+    modifications: Vec<SelfModification>,
+}
+
+impl SelfModDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn modifications(&self) -> &[SelfModification] {
+        &self.modifications
+    }
+
+    pub fn is_self_modifying(&self) -> bool {
+        !self.modifications.is_empty()
+    }
+
+    pub fn report(&self) -> String {
+        self.modifications
+            .iter()
+            .map(|modification| format!("{}\n", modification))
+            .collect()
+    }
+}
+
+fn decode_at(machine: &Machine, start: Word, patch: Option<(Word, Word)>) -> Option<Instruction> {
+    let cells: Vec<Word> = (start..start + 4)
+        .map(|address| match patch {
+            Some((patched, value)) if patched == address => value,
+            _ => machine.read_mem_at(address),
+        })
+        .collect();
+    let mut instruction = Instruction::decode_with(&cells, 0, machine.instruction_set())?;
+    instruction.address = start;
+    Some(instruction)
+}
+
+impl Observer for SelfModDetector {
+    fn on_execute(&mut self, ip: Word, op: Opcode) {
+        for address in ip..=ip + op.arity() as Word {
+            self.code.insert(address, ip);
+        }
+    }
+
+    fn on_write(&mut self, machine: &Machine, address: Word, old: Word) {
+        if let Some(&start) = self.code.get(&address) {
+            self.modifications.push(SelfModification {
+                writer_ip: machine.ip(),
+                address,
+                old_value: old,
+                new_value: machine.read_mem_at(address),
+                instruction_address: start,
+                old: decode_at(machine, start, Some((address, old))),
+                new: decode_at(machine, start, None),
+            });
+        }
+    }
+}
+
+#[test]
+fn test_detects_operand_patch() {
+    use crate::BufIo;
+
+    // reads a value into the operand of the `out` at address 2, then jumps back
+    // to it once, the second output comes from the patched address
+    let prog = vec![4, 9, 4, 9, 3, 3, 1105, 1, 2, 42, 7, 99];
+    let mut detector = SelfModDetector::new();
+    let mut io = BufIo::new(vec![10]);
+    let mut m = Machine::new(prog.clone());
+    for _ in 0..5 {
+        m.step_observed(&mut io, &mut detector);
+    }
+    assert_eq!(io.into_output(), &[42, 42, 7]);
+
+    let modification = &detector.modifications()[0];
+    assert_eq!(detector.modifications().len(), 1);
+    assert_eq!(modification.writer_ip, 4);
+    assert_eq!(modification.address, 3);
+    assert_eq!(modification.instruction_address, 2);
+    assert!(!modification.patches_opcode());
+    assert_eq!(modification.old.as_ref().unwrap().to_string(), "out [9]");
+    assert_eq!(modification.new.as_ref().unwrap().to_string(), "out [10]");
+}
+
+#[test]
+fn test_ignores_data_writes() {
+    use crate::BufIo;
+
+    let prog = vec![3, 7, 1001, 7, 1, 7, 99, 0];
+    let mut detector = SelfModDetector::new();
+    Machine::new(prog).execute_observed(&mut BufIo::new(vec![5]), &mut detector);
+    assert!(!detector.is_self_modifying());
+}
@@ -0,0 +1,433 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::ops::{Add, Mul, Range};
+use std::rc::Rc;
+
+use crate::machine::{decode, Op, ParamMode, StepResult, Word};
+use crate::DynResult;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Const(Word),
+    Var(Rc<str>),
+    Load(Rc<Expr>),
+    Add(Rc<Expr>, Rc<Expr>),
+    Mul(Rc<Expr>, Rc<Expr>),
+    LessThan(Rc<Expr>, Rc<Expr>),
+    Equals(Rc<Expr>, Rc<Expr>),
+}
+
+impl Expr {
+    pub fn var(name: &str) -> Self {
+        Expr::Var(name.into())
+    }
+
+    pub fn load(address: Expr) -> Self {
+        Expr::Load(Rc::new(address))
+    }
+
+    pub fn less_than(a: Expr, b: Expr) -> Self {
+        match (a.as_const(), b.as_const()) {
+            (Some(a), Some(b)) => Expr::Const((a < b) as _),
+            _ if a == b => Expr::Const(0),
+            _ => Expr::LessThan(Rc::new(a), Rc::new(b)),
+        }
+    }
+
+    pub fn equals(a: Expr, b: Expr) -> Self {
+        match (a.as_const(), b.as_const()) {
+            (Some(a), Some(b)) => Expr::Const((a == b) as _),
+            _ if a == b => Expr::Const(1),
+            _ => Expr::Equals(Rc::new(a), Rc::new(b)),
+        }
+    }
+
+    pub fn as_const(&self) -> Option<Word> {
+        match self {
+            Expr::Const(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn linear(&self) -> Option<Linear> {
+        match self {
+            Expr::Const(value) => Some(Linear::constant(*value)),
+            Expr::Var(name) => Some(Linear::var(name)),
+            Expr::Add(a, b) => Some(a.linear()?.sum(&b.linear()?)),
+            Expr::Mul(a, b) => {
+                let a = a.linear()?;
+                let b = b.linear()?;
+                match (a.as_const(), b.as_const()) {
+                    (Some(factor), _) => Some(b.scale(factor)),
+                    (_, Some(factor)) => Some(a.scale(factor)),
+                    _ => None,
+                }
+            }
+            Expr::Load(_) | Expr::LessThan(_, _) | Expr::Equals(_, _) => None,
+        }
+    }
+}
+
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(self, other: Expr) -> Expr {
+        match (self.as_const(), other.as_const()) {
+            (Some(a), Some(b)) => Expr::Const(a + b),
+            (Some(0), _) => other,
+            (_, Some(0)) => self,
+            _ => Expr::Add(Rc::new(self), Rc::new(other)),
+        }
+    }
+}
+
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, other: Expr) -> Expr {
+        match (self.as_const(), other.as_const()) {
+            (Some(a), Some(b)) => Expr::Const(a * b),
+            (Some(0), _) | (_, Some(0)) => Expr::Const(0),
+            (Some(1), _) => other,
+            (_, Some(1)) => self,
+            _ => Expr::Mul(Rc::new(self), Rc::new(other)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Const(value) => write!(f, "{}", value),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Load(address) => write!(f, "[{}]", address),
+            Expr::Add(a, b) => write!(f, "({} + {})", a, b),
+            Expr::Mul(a, b) => write!(f, "({} * {})", a, b),
+            Expr::LessThan(a, b) => write!(f, "({} < {})", a, b),
+            Expr::Equals(a, b) => write!(f, "({} == {})", a, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linear {
+    pub constant: Word,
+    pub terms: BTreeMap<Rc<str>, Word>,
+}
+
+impl Linear {
+    fn constant(constant: Word) -> Self {
+        Self {
+            constant,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    fn var(name: &Rc<str>) -> Self {
+        let mut terms = BTreeMap::new();
+        terms.insert(name.clone(), 1);
+        Self { constant: 0, terms }
+    }
+
+    fn as_const(&self) -> Option<Word> {
+        if self.terms.is_empty() {
+            Some(self.constant)
+        } else {
+            None
+        }
+    }
+
+    fn sum(mut self, other: &Linear) -> Self {
+        self.constant += other.constant;
+        for (name, coefficient) in &other.terms {
+            *self.terms.entry(name.clone()).or_insert(0) += coefficient;
+        }
+        self.terms.retain(|_, coefficient| *coefficient != 0);
+        self
+    }
+
+    fn scale(mut self, factor: Word) -> Self {
+        self.constant *= factor;
+        for coefficient in self.terms.values_mut() {
+            *coefficient *= factor;
+        }
+        self.terms.retain(|_, coefficient| *coefficient != 0);
+        self
+    }
+
+    pub fn coefficient(&self, name: &str) -> Word {
+        self.terms.get(name).copied().unwrap_or(0)
+    }
+
+    // Finds values for the variables (in the order of `domains`) that make the
+    // expression equal to `target`. All but the last variable are enumerated,
+    // the last one is solved for directly.
+    pub fn solve(&self, target: Word, domains: &[(&str, Range<Word>)]) -> Option<Vec<Word>> {
+        let unbound = self
+            .terms
+            .keys()
+            .any(|name| !domains.iter().any(|(domain, _)| **domain == **name));
+        if unbound || domains.iter().any(|(_, range)| range.is_empty()) {
+            return None;
+        }
+
+        let ((last_name, last_range), rest) = match domains.split_last() {
+            Some(split) => split,
+            None => return Some(vec![]).filter(|_| self.constant == target),
+        };
+        let last_coefficient = self.coefficient(last_name);
+        let mut values: Vec<Word> = rest.iter().map(|(_, range)| range.start).collect();
+
+        loop {
+            let partial = self.constant
+                + rest
+                    .iter()
+                    .zip(&values)
+                    .map(|((name, _), value)| self.coefficient(name) * value)
+                    .sum::<Word>();
+            let remainder = target - partial;
+
+            let candidate = if last_coefficient == 0 {
+                Some(last_range.start).filter(|_| remainder == 0)
+            } else if remainder % last_coefficient == 0 {
+                Some(remainder / last_coefficient)
+            } else {
+                None
+            };
+
+            if let Some(value) = candidate.filter(|value| last_range.contains(value)) {
+                values.push(value);
+                return Some(values);
+            }
+
+            let mut digit = 0;
+            loop {
+                if digit == values.len() {
+                    return None;
+                }
+                values[digit] += 1;
+                if values[digit] < rest[digit].1.end {
+                    break;
+                }
+                values[digit] = rest[digit].1.start;
+                digit += 1;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Linear {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, coefficient) in &self.terms {
+            match *coefficient {
+                1 => write!(f, "{} + ", name)?,
+                c => write!(f, "{}*{} + ", c, name)?,
+            }
+        }
+        write!(f, "{}", self.constant)
+    }
+}
+
+pub struct SymbolicMachine {
+    mem: Vec<Expr>,
+    ip: Word,
+    rel: Word,
+    modes: [ParamMode; 3],
+    input: VecDeque<Expr>,
+    output: Vec<Expr>,
+}
+
+impl SymbolicMachine {
+    pub fn new(mem_data: Vec<Word>) -> Self {
+        Self {
+            mem: mem_data.into_iter().map(Expr::Const).collect(),
+            ip: 0,
+            rel: 0,
+            modes: [ParamMode::Pointer; 3],
+            input: VecDeque::new(),
+            output: Vec::new(),
+        }
+    }
+
+    pub fn set_symbolic(&mut self, address: Word, name: &str) {
+        self.set_mem_at(address, Expr::var(name));
+    }
+
+    pub fn set_mem_at(&mut self, address: Word, value: Expr) {
+        assert!(address >= 0);
+        let address = address as usize;
+        if self.mem.len() <= address {
+            self.mem.resize(address + 1, Expr::Const(0));
+        }
+        self.mem[address] = value;
+    }
+
+    pub fn mem_at(&self, address: Word) -> Expr {
+        assert!(address >= 0);
+        self.mem
+            .get(address as usize)
+            .cloned()
+            .unwrap_or(Expr::Const(0))
+    }
+
+    pub fn memory(&self) -> &[Expr] {
+        &self.mem
+    }
+
+    pub fn push_input(&mut self, value: Expr) {
+        self.input.push_back(value);
+    }
+
+    pub fn output(&self) -> &[Expr] {
+        &self.output
+    }
+
+    fn concrete(&self, expr: Expr, what: &str) -> DynResult<Word> {
+        expr.as_const()
+            .ok_or_else(|| format!("Symbolic {} at IP {}: {}", what, self.ip, expr).into())
+    }
+
+    fn load(&self, address: Expr) -> DynResult<Expr> {
+        match address.as_const() {
+            Some(address) if address < 0 => {
+                Err(format!("Read from negative address {} at IP {}", address, self.ip).into())
+            }
+            Some(address) => Ok(self.mem_at(address)),
+            None => Ok(Expr::load(address)),
+        }
+    }
+
+    fn get_param(&self, param: usize) -> Expr {
+        self.mem_at(self.ip + param as Word + 1)
+    }
+
+    fn read(&self, param: usize) -> DynResult<Expr> {
+        let value = self.get_param(param);
+        match self.modes[param] {
+            ParamMode::Pointer => self.load(value),
+            ParamMode::Immediate => Ok(value),
+            ParamMode::Relative => self.load(value + Expr::Const(self.rel)),
+        }
+    }
+
+    fn write(&mut self, param: usize, val: Expr) -> DynResult<()> {
+        let address = match self.modes[param] {
+            ParamMode::Pointer => self.get_param(param),
+            ParamMode::Immediate => return Err("Cannot write to immediate value.".into()),
+            ParamMode::Relative => self.get_param(param) + Expr::Const(self.rel),
+        };
+        let address = self.concrete(address, "write address")?;
+        if address < 0 {
+            return Err(format!("Write to negative address {} at IP {}", address, self.ip).into());
+        }
+        self.set_mem_at(address, val);
+        Ok(())
+    }
+
+    pub fn execute(&mut self) -> DynResult<()> {
+        loop {
+            match self.step()? {
+                StepResult::Continue => {}
+                StepResult::Halt => return Ok(()),
+                StepResult::IoBlocked => {
+                    return Err(format!("Execution blocked on IO. IP: {}", self.ip).into())
+                }
+            }
+        }
+    }
+
+    pub fn step(&mut self) -> DynResult<StepResult> {
+        let op_byte = self.concrete(self.load(Expr::Const(self.ip))?, "instruction")?;
+        let (op, modes) = decode(op_byte)?;
+        self.modes = modes;
+        match op {
+            Op::Add => {
+                let val = self.read(0)? + self.read(1)?;
+                self.write(2, val)?;
+                self.ip += 4;
+            }
+            Op::Mul => {
+                let val = self.read(0)? * self.read(1)?;
+                self.write(2, val)?;
+                self.ip += 4;
+            }
+            Op::IoRead => match self.input.pop_front() {
+                Some(input) => {
+                    self.write(0, input)?;
+                    self.ip += 2;
+                }
+                None => return Ok(StepResult::IoBlocked),
+            },
+            Op::IoWrite => {
+                let val = self.read(0)?;
+                self.output.push(val);
+                self.ip += 2;
+            }
+            Op::JumpIfTrue | Op::JumpIfFalse => {
+                let condition = self.concrete(self.read(0)?, "jump condition")?;
+                let jump_on = matches!(op, Op::JumpIfTrue);
+                if (condition != 0) == jump_on {
+                    self.ip = self.concrete(self.read(1)?, "jump target")?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            Op::LessThan => {
+                let val = Expr::less_than(self.read(0)?, self.read(1)?);
+                self.write(2, val)?;
+                self.ip += 4;
+            }
+            Op::Equals => {
+                let val = Expr::equals(self.read(0)?, self.read(1)?);
+                self.write(2, val)?;
+                self.ip += 4;
+            }
+            Op::OffsetRel => {
+                self.rel += self.concrete(self.read(0)?, "relative offset")?;
+                self.ip += 2;
+            }
+            Op::Halt => return Ok(StepResult::Halt),
+        }
+        Ok(StepResult::Continue)
+    }
+}
+
+#[test]
+fn test_solve_linear_memory() -> DynResult<()> {
+    // mem[0] = (mem[13] + mem[14]) * 3 + mem[14]
+    let prog = vec![1, 13, 14, 0, 1002, 0, 3, 0, 1, 0, 14, 0, 99, 0, 0];
+    let mut m = SymbolicMachine::new(prog);
+    m.set_symbolic(13, "noun");
+    m.set_symbolic(14, "verb");
+    m.execute()?;
+
+    let result = m.mem_at(0).linear().ok_or("not linear")?;
+    assert_eq!(result.coefficient("noun"), 3);
+    assert_eq!(result.coefficient("verb"), 4);
+    assert_eq!(result.constant, 0);
+    assert_eq!(
+        result.solve(29, &[("noun", 0..10), ("verb", 0..10)]),
+        Some(vec![3, 5])
+    );
+    assert_eq!(result.solve(2, &[("noun", 0..10), ("verb", 0..10)]), None);
+    Ok(())
+}
+
+#[test]
+fn test_symbolic_input_comparison() -> DynResult<()> {
+    let prog = vec![3, 9, 1008, 9, 8, 10, 4, 10, 99, 0, 0];
+    let mut m = SymbolicMachine::new(prog);
+    m.push_input(Expr::var("x"));
+    m.execute()?;
+    assert_eq!(m.output(), &[Expr::equals(Expr::var("x"), Expr::Const(8))]);
+    assert_eq!(m.output()[0].linear(), None);
+    Ok(())
+}
+
+#[test]
+fn test_symbolic_jump_fails() {
+    let prog = vec![3, 7, 1005, 7, 0, 99, 99, 0];
+    let mut m = SymbolicMachine::new(prog);
+    m.push_input(Expr::var("x"));
+    assert!(m.execute().is_err());
+}
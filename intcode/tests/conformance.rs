@@ -0,0 +1,256 @@
+use intcode::*;
+use std::fs;
+use std::path::Path;
+
+// Runs every case in tests/conformance, see the README there for the format.
+
+const STEP_LIMIT: usize = 1_000_000;
+
+#[derive(Debug, Default)]
+struct Case {
+    program: Vec<Word>,
+    amplifiers: Option<bool>,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Default)]
+struct Run {
+    input: Vec<Word>,
+    output: Option<Vec<Word>>,
+    memory: Option<Vec<Word>>,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Outcome {
+    output: Vec<Word>,
+    memory: Vec<Word>,
+    error: Option<&'static str>,
+}
+
+fn parse_words(text: &str) -> DynResult<Vec<Word>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(|word| Ok(word.parse()?))
+        .collect()
+}
+
+fn parse_case(text: &str) -> DynResult<Case> {
+    let mut case = Case::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut split = line.splitn(2, ':');
+        let key = split.next().unwrap_or("").trim();
+        let value = split
+            .next()
+            .ok_or_else(|| format!("Expected `key: value`, got {}", line))?
+            .trim();
+
+        if key == "input" {
+            case.runs.push(Run {
+                input: parse_words(value)?,
+                ..Run::default()
+            });
+            continue;
+        }
+        let run = case.runs.last_mut();
+        match (key, run) {
+            ("program", _) => case.program.extend(parse_words(value)?),
+            ("amplifiers", _) => {
+                case.amplifiers = Some(match value {
+                    "chain" => false,
+                    "feedback" => true,
+                    _ => return Err(format!("Unknown amplifier mode {}", value).into()),
+                })
+            }
+            ("output", Some(run)) => run.output = Some(parse_words(value)?),
+            ("memory", Some(run)) => run.memory = Some(parse_words(value)?),
+            ("error", Some(run)) => run.error = Some(value.to_string()),
+            ("output", None) | ("memory", None) | ("error", None) => {
+                return Err(format!("`{}` before the first `input`", key).into())
+            }
+            _ => return Err(format!("Unknown key {}", key).into()),
+        }
+    }
+    if case.runs.is_empty() {
+        return Err("No runs, add an `input` line".into());
+    }
+    Ok(case)
+}
+
+fn fault_kind(fault: &Fault) -> &'static str {
+    match fault {
+        Fault::InvalidInstruction { .. } => "invalid-instruction",
+        Fault::ImmediateWrite { .. } => "immediate-write",
+        Fault::NegativeAddress { .. } => "negative-address",
+    }
+}
+
+// Errors with the kind of the fault, or `step-limit` for a program that runs
+// for too long.
+fn step_until_stop(machine: &mut Machine, io: &mut impl Io) -> Result<StepResult, &'static str> {
+    for _ in 0..STEP_LIMIT {
+        match machine
+            .try_step(io, &mut ())
+            .map_err(|fault| fault_kind(&fault))?
+        {
+            StepResult::Continue => {}
+            result => return Ok(result),
+        }
+    }
+    Err("step-limit")
+}
+
+fn run_single(program: &[Word], input: &[Word]) -> Outcome {
+    let mut machine = Machine::new(program.to_vec());
+    let mut io = BufIo::new(input.to_vec());
+    let error = match step_until_stop(&mut machine, &mut io) {
+        Ok(StepResult::IoBlocked) => Some("blocked"),
+        Ok(_) => None,
+        Err(kind) => Some(kind),
+    };
+    Outcome {
+        output: io.into_output(),
+        memory: machine.memory().to_vec(),
+        error,
+    }
+}
+
+fn pair(buffers: &mut [IoBuffer], a: usize, b: usize) -> (&mut IoBuffer, &mut IoBuffer) {
+    if a < b {
+        let (left, right) = buffers.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = buffers.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+// One amplifier per phase in a chain, each fed its phase first. The first one also
+// gets a 0, with feedback the last one loops its output back to the first.
+fn run_amplifiers(program: &[Word], phases: &[Word], feedback: bool) -> Outcome {
+    let mut amps: Vec<Machine> = phases
+        .iter()
+        .map(|_| Machine::new(program.to_vec()))
+        .collect();
+    let mut buffers: Vec<IoBuffer> = phases
+        .iter()
+        .map(|phase| IoBuffer::with_data(&[*phase]))
+        .collect();
+    buffers[0].push(0);
+    buffers.push(IoBuffer::new());
+    let last = amps.len();
+
+    let mut error = Some("step-limit");
+    'run: for _ in 0..STEP_LIMIT {
+        let mut result = StepResult::Halt;
+        for (idx, amp) in amps.iter_mut().enumerate() {
+            let output = if feedback && idx + 1 == last {
+                0
+            } else {
+                idx + 1
+            };
+            let (input, output) = pair(&mut buffers, idx, output);
+            match amp.try_step(&mut PipedIo::new(input, output), &mut ()) {
+                Ok(step) => result = result.join(step),
+                Err(fault) => {
+                    error = Some(fault_kind(&fault));
+                    break 'run;
+                }
+            }
+        }
+        match result {
+            StepResult::Continue => {}
+            StepResult::Halt => {
+                error = None;
+                break;
+            }
+            StepResult::IoBlocked => {
+                error = Some("blocked");
+                break;
+            }
+        }
+    }
+
+    let output = if feedback { 0 } else { last };
+    Outcome {
+        output: buffers[output].drain().collect(),
+        memory: amps[last - 1].memory().to_vec(),
+        error,
+    }
+}
+
+fn check_run(case: &Case, run: &Run) -> Result<(), String> {
+    let outcome = match case.amplifiers {
+        Some(feedback) => run_amplifiers(&case.program, &run.input, feedback),
+        None => run_single(&case.program, &run.input),
+    };
+    if outcome.error != run.error.as_deref() {
+        return Err(format!(
+            "expected {}, got {}",
+            run.error.as_deref().unwrap_or("halt"),
+            outcome.error.unwrap_or("halt")
+        ));
+    }
+    if let Some(output) = &run.output {
+        if &outcome.output != output {
+            return Err(format!(
+                "expected output {:?}, got {:?}",
+                output, outcome.output
+            ));
+        }
+    }
+    if let Some(memory) = &run.memory {
+        let actual = &outcome.memory[..memory.len().min(outcome.memory.len())];
+        if actual != &memory[..] {
+            return Err(format!("expected memory {:?}, got {:?}", memory, actual));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn conformance() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "case"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "No cases in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let case = match parse_case(&fs::read_to_string(path).unwrap()) {
+            Ok(case) => case,
+            Err(error) => {
+                failures.push(format!("{}: {}", name, error));
+                continue;
+            }
+        };
+        for (idx, run) in case.runs.iter().enumerate() {
+            if let Err(error) = check_run(&case, run) {
+                failures.push(format!(
+                    "{} run {} {:?}: {}",
+                    name,
+                    idx + 1,
+                    run.input,
+                    error
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed:\n{}",
+        failures.len(),
+        paths.len(),
+        failures.join("\n")
+    );
+}
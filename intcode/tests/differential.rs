@@ -0,0 +1,324 @@
+use intcode::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+const STEP_LIMIT: usize = 1000;
+const ADDRESS_LIMIT: Word = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Halt {
+    Finished,
+    Blocked,
+    Fault(&'static str),
+    StepLimit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Run {
+    halt: Halt,
+    output: Vec<Word>,
+    memory: Vec<Word>,
+}
+
+fn trimmed(mut memory: Vec<Word>) -> Vec<Word> {
+    while memory.last() == Some(&0) {
+        memory.pop();
+    }
+    memory
+}
+
+enum Stop {
+    Halt(Halt),
+    // the case leaves the ground the backends are expected to agree on
+    // (arithmetic overflow, gigantic memory), skip it
+    Discard,
+}
+
+// Deliberately naive interpreter written straight from the puzzle text. It
+// shares no code with `Machine`, so the two can be compared against each other.
+struct Reference {
+    mem: HashMap<Word, Word>,
+    ip: Word,
+    rb: Word,
+}
+
+impl Reference {
+    fn load(&self, address: Word) -> Result<Word, Stop> {
+        if address < 0 {
+            return Err(Stop::Halt(Halt::Fault("negative address")));
+        }
+        Ok(self.mem.get(&address).copied().unwrap_or(0))
+    }
+
+    fn address(&self, param: Word) -> Result<Word, Stop> {
+        let mode = self.load(self.ip)? / [100, 1000, 10000][param as usize - 1] % 10;
+        let cell = self.ip + param;
+        match mode {
+            0 => self.load(cell),
+            1 => Ok(cell),
+            _ => Ok(self.load(cell)? + self.rb),
+        }
+    }
+
+    fn read(&self, param: Word) -> Result<Word, Stop> {
+        self.load(self.address(param)?)
+    }
+
+    fn write(&mut self, param: Word, value: Word) -> Result<(), Stop> {
+        let mode = self.load(self.ip)? / [100, 1000, 10000][param as usize - 1] % 10;
+        if mode == 1 {
+            return Err(Stop::Halt(Halt::Fault("immediate write")));
+        }
+        let address = self.address(param)?;
+        if address < 0 {
+            return Err(Stop::Halt(Halt::Fault("negative address")));
+        }
+        if address > ADDRESS_LIMIT {
+            return Err(Stop::Discard);
+        }
+        self.mem.insert(address, value);
+        Ok(())
+    }
+
+    fn step(
+        &mut self,
+        input: &mut impl Iterator<Item = Word>,
+        output: &mut Vec<Word>,
+    ) -> Result<(), Stop> {
+        let instruction = self.load(self.ip)?;
+        let modes = [
+            instruction / 100 % 10,
+            instruction / 1000 % 10,
+            instruction / 10000 % 10,
+        ];
+        if modes.iter().any(|mode| !(0..=2).contains(mode)) {
+            return Err(Stop::Halt(Halt::Fault("invalid instruction")));
+        }
+
+        match instruction % 100 {
+            opcode @ 1 | opcode @ 2 | opcode @ 7 | opcode @ 8 => {
+                let a = self.read(1)?;
+                let b = self.read(2)?;
+                let result = match opcode {
+                    1 => a.checked_add(b).ok_or(Stop::Discard)?,
+                    2 => a.checked_mul(b).ok_or(Stop::Discard)?,
+                    7 => (a < b) as Word,
+                    _ => (a == b) as Word,
+                };
+                self.write(3, result)?;
+                self.ip += 4;
+            }
+            3 => {
+                let value = input.next().ok_or(Stop::Halt(Halt::Blocked))?;
+                self.write(1, value)?;
+                self.ip += 2;
+            }
+            4 => {
+                output.push(self.read(1)?);
+                self.ip += 2;
+            }
+            opcode @ 5 | opcode @ 6 => {
+                let condition = self.read(1)?;
+                if (condition != 0) == (opcode == 5) {
+                    self.ip = self.read(2)?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            9 => {
+                self.rb += self.read(1)?;
+                self.ip += 2;
+            }
+            99 => return Err(Stop::Halt(Halt::Finished)),
+            _ => return Err(Stop::Halt(Halt::Fault("invalid instruction"))),
+        }
+        Ok(())
+    }
+}
+
+fn run_reference(prog: &[Word], input: &[Word]) -> Option<Run> {
+    let mut reference = Reference {
+        mem: (0..).zip(prog.iter().copied()).collect(),
+        ip: 0,
+        rb: 0,
+    };
+    let mut input = input.iter().copied();
+    let mut output = Vec::new();
+
+    let mut halt = Halt::StepLimit;
+    for _ in 0..STEP_LIMIT {
+        match reference.step(&mut input, &mut output) {
+            Ok(()) => {}
+            Err(Stop::Halt(reason)) => {
+                halt = reason;
+                break;
+            }
+            Err(Stop::Discard) => return None,
+        }
+    }
+
+    let len = reference
+        .mem
+        .keys()
+        .max()
+        .map_or(0, |max| *max as usize + 1);
+    let mut memory = vec![0; len];
+    for (address, value) in &reference.mem {
+        memory[*address as usize] = *value;
+    }
+    Some(Run {
+        halt,
+        output,
+        memory: trimmed(memory),
+    })
+}
+
+fn fault_kind(fault: &Fault) -> &'static str {
+    match fault {
+        Fault::InvalidInstruction { .. } => "invalid instruction",
+        Fault::ImmediateWrite { .. } => "immediate write",
+        Fault::NegativeAddress { .. } => "negative address",
+    }
+}
+
+fn run_machine(prog: &[Word], input: &[Word]) -> Run {
+    let mut machine = Machine::new(prog.to_vec());
+    let mut input = IoBuffer::with_data(input);
+    let mut output = IoBuffer::new();
+
+    let mut halt = Halt::StepLimit;
+    for _ in 0..STEP_LIMIT {
+        match machine.try_step(&mut PipedIo::new(&mut input, &mut output), &mut ()) {
+            Ok(StepResult::Continue) => {}
+            Ok(StepResult::Halt) => {
+                halt = Halt::Finished;
+                break;
+            }
+            Ok(StepResult::IoBlocked) => {
+                halt = Halt::Blocked;
+                break;
+            }
+            Err(fault) => {
+                halt = Halt::Fault(fault_kind(&fault));
+                break;
+            }
+        }
+    }
+
+    Run {
+        halt,
+        output: output.into_inner().into_iter().collect(),
+        memory: trimmed(machine.memory().to_vec()),
+    }
+}
+
+// The symbolic engine only reports errors as strings, so its faults are
+// compared without their kind.
+fn run_symbolic(prog: &[Word], input: &[Word]) -> Run {
+    let mut machine = SymbolicMachine::new(prog.to_vec());
+    for value in input {
+        machine.push_input(Expr::Const(*value));
+    }
+
+    let mut halt = Halt::StepLimit;
+    for _ in 0..STEP_LIMIT {
+        match machine.step() {
+            Ok(StepResult::Continue) => {}
+            Ok(StepResult::Halt) => {
+                halt = Halt::Finished;
+                break;
+            }
+            Ok(StepResult::IoBlocked) => {
+                halt = Halt::Blocked;
+                break;
+            }
+            Err(_) => {
+                halt = Halt::Fault("fault");
+                break;
+            }
+        }
+    }
+
+    let concrete = |expr: &Expr| expr.as_const().expect("symbolic value from concrete run");
+    Run {
+        halt,
+        output: machine.output().iter().map(concrete).collect(),
+        memory: trimmed(machine.memory().iter().map(concrete).collect()),
+    }
+}
+
+fn erase_fault_kind(mut run: Run) -> Run {
+    if let Halt::Fault(_) = run.halt {
+        run.halt = Halt::Fault("fault");
+    }
+    run
+}
+
+fn arity(opcode: Word) -> usize {
+    match opcode {
+        1 | 2 | 7 | 8 => 3,
+        5 | 6 => 2,
+        3 | 4 | 9 => 1,
+        _ => 0,
+    }
+}
+
+fn write_param(opcode: Word) -> Option<usize> {
+    match opcode {
+        1 | 2 | 7 | 8 => Some(2),
+        3 => Some(0),
+        _ => None,
+    }
+}
+
+// Valid opcodes and modes, never an immediate-mode write. The program is still
+// free to overwrite itself into something invalid while it runs.
+fn instruction() -> impl Strategy<Value = Vec<Word>> {
+    (
+        prop::sample::select(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 99]),
+        prop::array::uniform3(0..3 as Word),
+        prop::array::uniform3(-4..40 as Word),
+    )
+        .prop_map(|(opcode, mut modes, params)| {
+            if let Some(param) = write_param(opcode) {
+                if modes[param] == 1 {
+                    modes[param] = 0;
+                }
+            }
+            let word = opcode + modes[0] * 100 + modes[1] * 1000 + modes[2] * 10000;
+            std::iter::once(word)
+                .chain(params[..arity(opcode)].iter().copied())
+                .collect()
+        })
+}
+
+fn program() -> impl Strategy<Value = Vec<Word>> {
+    (vec(instruction(), 1..16), vec(-10..60 as Word, 0..16))
+        .prop_map(|(code, data)| code.into_iter().flatten().chain(data).collect())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    #[test]
+    fn backends_match_reference(prog in program(), input in vec(-50..50 as Word, 0..6)) {
+        let expected = run_reference(&prog, &input);
+        prop_assume!(expected.is_some());
+        let expected = expected.unwrap();
+
+        prop_assert_eq!(&run_machine(&prog, &input), &expected);
+        prop_assert_eq!(run_symbolic(&prog, &input), erase_fault_kind(expected));
+    }
+}
+
+#[test]
+fn test_reference_quine() {
+    let prog = vec![
+        109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+    ];
+    let run = run_reference(&prog, &[]).unwrap();
+    assert_eq!(run.halt, Halt::Finished);
+    assert_eq!(run.output, prog);
+    assert_eq!(run, run_machine(&prog, &[]));
+}
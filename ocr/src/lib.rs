@@ -0,0 +1,104 @@
+// Reads the block letters puzzles draw, 6 pixels high and usually 4 wide with
+// a blank column between letters.
+const FONT: &[(char, &str)] = &[
+    ('A', ".##. #..# #..# #### #..# #..#"),
+    ('B', "###. #..# ###. #..# #..# ###."),
+    ('C', ".##. #..# #... #... #..# .##."),
+    ('E', "#### #... ###. #... #... ####"),
+    ('F', "#### #... ###. #... #... #..."),
+    ('G', ".##. #..# #... #.## #..# .###"),
+    ('H', "#..# #..# #### #..# #..# #..#"),
+    ('I', "### .#. .#. .#. .#. ###"),
+    ('J', "..## ...# ...# ...# #..# .##."),
+    ('K', "#..# #.#. ##.. #.#. #.#. #..#"),
+    ('L', "#... #... #... #... #... ####"),
+    ('O', ".##. #..# #..# #..# #..# .##."),
+    ('P', "###. #..# #..# ###. #... #..."),
+    ('R', "###. #..# #..# ###. #.#. #..#"),
+    ('S', ".### #... #... .##. ...# ###."),
+    ('U', "#..# #..# #..# #..# #..# .##."),
+    ('Y', "#...# #...# .#.#. ..#.. ..#.. ..#.."),
+    ('Z', "#### ...# ..#. .#.. #... ####"),
+];
+
+pub const HEIGHT: usize = 6;
+
+// Columns of the glyph without the blank ones on its sides, a bit per row.
+fn columns(rows: &[Vec<bool>]) -> Vec<u8> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let columns: Vec<u8> = (0..width)
+        .map(|x| {
+            rows.iter().enumerate().fold(0, |column, (y, row)| {
+                column | (row.get(x).copied().unwrap_or(false) as u8) << y
+            })
+        })
+        .collect();
+    let start = columns
+        .iter()
+        .position(|c| *c != 0)
+        .unwrap_or(columns.len());
+    let end = columns
+        .iter()
+        .rposition(|c| *c != 0)
+        .map_or(start, |end| end + 1);
+    columns[start..end].to_vec()
+}
+
+fn glyph(pattern: &str) -> Vec<u8> {
+    let rows: Vec<Vec<bool>> = pattern
+        .split(' ')
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect();
+    columns(&rows)
+}
+
+// Turns the pixels into text, rows from top to bottom. Blank rows around the
+// letters are ignored, letters are separated by blank columns. Shapes not in
+// the font read as `?`.
+pub fn recognize(rows: &[Vec<bool>]) -> String {
+    let lit = |row: &Vec<bool>| row.contains(&true);
+    let top = rows.iter().position(lit).unwrap_or(rows.len());
+    let bottom = rows.iter().rposition(lit).map_or(top, |bottom| bottom + 1);
+    let rows = &rows[top..bottom];
+    if rows.is_empty() {
+        return String::new();
+    }
+    if rows.len() != HEIGHT {
+        return "?".to_string();
+    }
+
+    let font: Vec<(char, Vec<u8>)> = FONT.iter().map(|(c, p)| (*c, glyph(p))).collect();
+    columns(rows)
+        .split(|column| *column == 0)
+        .filter(|letter| !letter.is_empty())
+        .map(|letter| {
+            font.iter()
+                .find(|(_, glyph)| glyph[..] == *letter)
+                .map_or('?', |(c, _)| *c)
+        })
+        .collect()
+}
+
+// Same for text, `#` being a lit pixel.
+pub fn recognize_str(text: &str) -> String {
+    let rows: Vec<Vec<bool>> = text
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+    recognize(&rows)
+}
+
+#[test]
+fn test_recognize() {
+    let text = "
+.##..###...##..####.####..##..#..#.###....##.#..#.#.....##..###..###...###.#..#.#...#.####
+#..#.#..#.#..#.#....#....#..#.#..#..#......#.#.#..#....#..#.#..#.#..#.#....#..#.#...#....#
+#..#.###..#....###..###..#....####..#......#.##...#....#..#.#..#.#..#.#....#..#..#.#....#.
+####.#..#.#....#....#....#.##.#..#..#......#.#.#..#....#..#.###..###...##..#..#...#....#..
+#..#.#..#.#..#.#....#....#..#.#..#..#...#..#.#.#..#....#..#.#....#.#.....#.#..#...#...#...
+#..#.###...##..####.#.....###.#..#.###...##..#..#.####..##..#....#..#.###...##....#...####
+";
+    assert_eq!(recognize_str(text), "ABCEFGHIJKLOPRSUYZ");
+    assert_eq!(recognize_str("\n#.#\n"), "?");
+    assert_eq!(recognize_str("....\n...."), "");
+}
@@ -0,0 +1,67 @@
+#!/bin/sh
+# Benchmarks the working tree against a git revision and fails when criterion
+# finds a regression, e.g. before committing a change to the intcode crate:
+#
+#   scripts/bench-compare.sh [REV] [BENCH]
+#
+# REV defaults to HEAD, BENCH to both `days` and `machine`. The revision is
+# measured once and kept as a criterion baseline named after its commit in
+# target/criterion, later runs compare against the stored baseline. Remove
+# target/criterion to measure it again. Changes within BENCH_NOISE (default
+# 0.10, i.e. 10%) are taken as noise.
+#
+# Both sides are built from the same checkout in target/bench-worktree, as the
+# path of a crate alone changes its code layout enough to show up here.
+set -eu
+
+root=$(git rev-parse --show-toplevel)
+rev=$(git rev-parse --short "${1:-HEAD}")
+bench=${2:-}
+baseline="rev-$rev"
+worktree="$root/target/bench-worktree"
+export CRITERION_HOME="$root/target/criterion"
+export CARGO_TARGET_DIR="$root/target"
+options="--noise-threshold ${BENCH_NOISE:-0.10}"
+
+run_bench() {
+    if [ -n "$bench" ]; then
+        (cd "$worktree" && cargo bench -q -p aoc --bench "$bench" -- "$@")
+    else
+        (cd "$worktree" && cargo bench -q -p aoc -- "$@")
+    fi
+}
+
+checkout() {
+    if [ -e "$worktree/.git" ]; then
+        git -C "$worktree" checkout -q --detach "$1"
+    else
+        git -C "$root" worktree add -q --detach "$worktree" "$1"
+    fi
+}
+
+# the working tree as a commit, untracked files included
+index=$(mktemp -u)
+GIT_INDEX_FILE=$index git -C "$root" add -A
+tree=$(GIT_INDEX_FILE=$index git -C "$root" write-tree)
+rm -f "$index"
+current=$(git -C "$root" commit-tree "$tree" -p HEAD -m "Benchmarked working tree")
+
+if [ -z "$(find "$CRITERION_HOME" -type d -name "$baseline" 2>/dev/null | head -n 1)" ]; then
+    echo "Measuring baseline $baseline"
+    checkout "$rev"
+    run_bench --save-baseline "$baseline" $options
+fi
+
+echo "Comparing against $baseline"
+checkout "$current"
+log=$(mktemp)
+run_bench --baseline "$baseline" $options | tee "$log"
+if grep -q "regressed" "$log"; then
+    echo "Performance regressed against $rev:"
+    # names start a line, long ones have the timings on the next one
+    awk '/^[^ ]/ && !/^(Benchmarking|Found|Gnuplot)/ { name = $0; sub(/ +time:.*/, "", name) }
+        /regressed/ { print "    " name }' "$log"
+    rm -f "$log"
+    exit 1
+fi
+rm -f "$log"